@@ -0,0 +1,341 @@
+//! Combining two sequential patches into one, like `combinediff`
+
+use crate::edit::build_hunk;
+use crate::{Error, Hunk, Line, LineType, PatchSet, PatchedFile, Result};
+
+/// A line of one of the two patches, seen from the intermediate version
+enum Item<'a> {
+    /// A line only in the first or last version: removed by the first patch
+    /// or added by the second one
+    Gone(&'a Line, Option<&'a Line>),
+    /// A line of the intermediate version, `None` when the patch does not
+    /// show it
+    Kept {
+        line: Option<&'a Line>,
+        changed: bool,
+        marker: Option<&'a Line>,
+    },
+}
+
+/// The lines of `hunks` over intermediate lines `lo..hi`, where `hunks` are
+/// the hunks of the first patch when `first` and of the second one otherwise
+fn stream<'a>(hunks: &[&'a Hunk], first: bool, lo: usize, hi: usize) -> Vec<Item<'a>> {
+    let unknown = || Item::Kept {
+        line: None,
+        changed: false,
+        marker: None,
+    };
+    let mut items = vec![];
+    let mut pos = lo;
+    for hunk in hunks {
+        let (source_pos, target_pos) = hunk.start_positions();
+        let start = if first { target_pos } else { source_pos };
+        items.extend((pos..start).map(|_| unknown()));
+        pos = start;
+        for line in &hunk.lines {
+            let (gone, changed) = match line.line_type {
+                LineType::Added => (!first, first),
+                LineType::Removed => (first, !first),
+                LineType::Context => (false, false),
+                LineType::Empty => {
+                    match items.last_mut() {
+                        Some(Item::Gone(_, marker)) | Some(Item::Kept { marker, .. }) => {
+                            *marker = Some(line)
+                        }
+                        None => {}
+                    }
+                    continue;
+                }
+            };
+            if gone {
+                items.push(Item::Gone(line, None));
+            } else {
+                items.push(Item::Kept {
+                    line: Some(line),
+                    changed,
+                    marker: None,
+                });
+                pos += 1;
+            }
+        }
+    }
+    items.extend((pos..hi).map(|_| unknown()));
+    items
+}
+
+fn push_line(lines: &mut Vec<Line>, line: &Line, line_type: LineType, marker: Option<&Line>) {
+    let mut line = line.clone();
+    line.line_type = line_type;
+    lines.push(line);
+    lines.extend(marker.cloned());
+}
+
+/// Turn lines that a block of changes removes and adds back into context
+fn cancel_reverted(lines: Vec<Line>) -> Vec<Line> {
+    let mut result = Vec::with_capacity(lines.len());
+    let mut idx = 0;
+    while idx < lines.len() {
+        if !lines[idx].is_added() && !lines[idx].is_removed() {
+            result.push(lines[idx].clone());
+            idx += 1;
+            continue;
+        }
+        let end = lines[idx..]
+            .iter()
+            .position(Line::is_context)
+            .map_or(lines.len(), |len| idx + len);
+        let block = &lines[idx..end];
+        idx = end;
+        if block.iter().any(|l| l.line_type == LineType::Empty) {
+            result.extend(block.iter().cloned());
+            continue;
+        }
+        let removed: Vec<&Line> = block.iter().filter(|l| l.is_removed()).collect();
+        let added: Vec<&Line> = block.iter().filter(|l| l.is_added()).collect();
+        let same = |(r, a): &(&&Line, &&Line)| r.value == a.value;
+        let prefix = removed.iter().zip(&added).take_while(same).count();
+        let suffix = removed[prefix..]
+            .iter()
+            .rev()
+            .zip(added[prefix..].iter().rev())
+            .take_while(same)
+            .count();
+        let context = |line: &Line| {
+            let mut line = line.clone();
+            line.line_type = LineType::Context;
+            line
+        };
+        result.extend(removed[..prefix].iter().map(|l| context(l)));
+        result.extend(
+            removed[prefix..removed.len() - suffix]
+                .iter()
+                .map(|&l| l.clone()),
+        );
+        result.extend(
+            added[prefix..added.len() - suffix]
+                .iter()
+                .map(|&l| l.clone()),
+        );
+        result.extend(removed[removed.len() - suffix..].iter().map(|l| context(l)));
+    }
+    result
+}
+
+/// Combine the hunks of both patches over intermediate lines `lo..hi`
+fn combine_lines(first: &[&Hunk], second: &[&Hunk], lo: usize, hi: usize) -> Result<Vec<Line>> {
+    let mut first = stream(first, true, lo, hi).into_iter().peekable();
+    let mut second = stream(second, false, lo, hi).into_iter().peekable();
+    let mut lines = vec![];
+    loop {
+        if let Some(Item::Gone(line, marker)) = first.peek() {
+            push_line(&mut lines, line, LineType::Removed, *marker);
+            first.next();
+            continue;
+        }
+        if let Some(Item::Gone(line, marker)) = second.peek() {
+            push_line(&mut lines, line, LineType::Added, *marker);
+            second.next();
+            continue;
+        }
+        match (first.next(), second.next()) {
+            (None, None) => return Ok(cancel_reverted(lines)),
+            (
+                Some(Item::Kept {
+                    line: a,
+                    changed: added,
+                    marker: a_marker,
+                }),
+                Some(Item::Kept {
+                    line: b,
+                    changed: removed,
+                    marker: b_marker,
+                }),
+            ) => {
+                let line = match (a, b) {
+                    (Some(a), Some(b)) if a.value != b.value => {
+                        return Err(Error::SourceMismatch(b.to_string()))
+                    }
+                    (Some(line), _) | (None, Some(line)) => line,
+                    (None, None) => return Err(Error::SourceMismatch(format!("@@ -{} @@", lo))),
+                };
+                let marker = b_marker.or(a_marker);
+                match (added, removed) {
+                    (true, true) => {}
+                    (true, false) => push_line(&mut lines, line, LineType::Added, marker),
+                    (false, true) => push_line(&mut lines, line, LineType::Removed, marker),
+                    (false, false) => push_line(&mut lines, line, LineType::Context, marker),
+                }
+            }
+            (_, Some(Item::Kept { line: Some(b), .. })) => {
+                return Err(Error::SourceMismatch(b.to_string()))
+            }
+            _ => return Err(Error::SourceMismatch(format!("@@ -{} @@", lo))),
+        }
+    }
+}
+
+impl PatchedFile {
+    /// Combine this patch with `next`, a patch of the same file made on top
+    /// of it, into a single patch, like `combinediff`
+    ///
+    /// Only the hunks are needed, not the file itself. The file header is
+    /// the one of this patch, without its `index` line, unless `next`
+    /// deletes the file.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let first: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+B\n".parse().unwrap();
+    /// let second: PatchSet = "--- a/f\n+++ b/f\n@@ -2 +2,2 @@\n B\n+c\n".parse().unwrap();
+    /// let combined = first[0].combine(&second[0]).unwrap();
+    /// assert_eq!("@@ -1,2 +1,3 @@ \n a\n-b\n+B\n+c", combined[0].to_string());
+    /// ```
+    pub fn combine(&self, next: &PatchedFile) -> Result<PatchedFile> {
+        // ranges of the intermediate version each hunk covers
+        let mut ranges: Vec<(usize, usize, bool, &Hunk)> = self
+            .hunks
+            .iter()
+            .map(|h| {
+                let (_, target_pos) = h.start_positions();
+                (target_pos, target_pos + h.target_length, true, h)
+            })
+            .chain(next.hunks.iter().map(|h| {
+                let (source_pos, _) = h.start_positions();
+                (source_pos, source_pos + h.source_length, false, h)
+            }))
+            .collect();
+        ranges.sort_by_key(|&(start, end, first, _)| (start, end, !first));
+
+        let mut hunks = vec![];
+        // line count difference each patch made before the current range
+        let (mut first_offset, mut second_offset) = (0isize, 0isize);
+        let mut idx = 0;
+        while idx < ranges.len() {
+            let (lo, mut hi) = (ranges[idx].0, ranges[idx].1);
+            let (mut first, mut second) = (vec![], vec![]);
+            while idx < ranges.len() && ranges[idx].0 <= hi {
+                let (_, end, is_first, hunk) = ranges[idx];
+                hi = hi.max(end);
+                if is_first {
+                    first.push(hunk);
+                } else {
+                    second.push(hunk);
+                }
+                idx += 1;
+            }
+            let lines = combine_lines(&first, &second, lo, hi)?;
+            if lines.iter().any(|l| l.is_added() || l.is_removed()) {
+                let source_pos = (lo as isize - first_offset) as usize;
+                let target_pos = (lo as isize + second_offset) as usize;
+                let section_header = first
+                    .first()
+                    .or_else(|| second.first())
+                    .map_or("", |h| h.section_header.as_str());
+                hunks.push(build_hunk(source_pos, target_pos, section_header, lines));
+            }
+            for hunk in first {
+                first_offset += hunk.target_length as isize - hunk.source_length as isize;
+            }
+            for hunk in second {
+                second_offset += hunk.target_length as isize - hunk.source_length as isize;
+            }
+        }
+
+        let header = if next.is_removed_file() {
+            &next.header
+        } else {
+            &self.header
+        };
+        let mut properties = self.properties.clone();
+        properties.extend(next.properties.iter().cloned());
+        Ok(PatchedFile {
+            header: header
+                .iter()
+                .filter(|l| !l.starts_with("index "))
+                .cloned()
+                .collect(),
+            source_file: self.source_file.clone(),
+            source_timestamp: self.source_timestamp.clone(),
+            source_revision: self.source_revision,
+            target_file: next.target_file.clone(),
+            target_timestamp: next.target_timestamp.clone(),
+            target_revision: next.target_revision,
+            properties,
+            hunks,
+            source_quoted: self.source_quoted,
+            target_quoted: next.target_quoted,
+        })
+    }
+}
+
+/// Value of the first header line of `file` starting with `prefix`
+fn header_value<'a>(file: &'a PatchedFile, prefix: &str) -> Option<&'a str> {
+    file.header.iter().find_map(|l| l.strip_prefix(prefix))
+}
+
+/// Do `first` and `next` together leave the mode of the file and, for a
+/// binary file, its content as they were
+fn reverted_header(first: &PatchedFile, next: &PatchedFile) -> bool {
+    let old_mode = header_value(first, "old mode ").or_else(|| header_value(next, "old mode "));
+    let new_mode = header_value(next, "new mode ").or_else(|| header_value(first, "new mode "));
+    if old_mode != new_mode {
+        return false;
+    }
+    if !first.is_binary() && !next.is_binary() {
+        return true;
+    }
+    // the blobs of the `index` lines tell whether the content came back
+    let blobs = |file| {
+        header_value(file, "index ")
+            .and_then(|v| v.split(' ').next())
+            .and_then(|v| v.split_once(".."))
+    };
+    match (blobs(first), blobs(next)) {
+        (Some((old, _)), Some((_, new))) => old == new,
+        _ => false,
+    }
+}
+
+impl PatchSet {
+    /// Combine this patch set with `next`, made on top of it, into a single
+    /// patch set, like `combinediff`
+    ///
+    /// Files changed by only one of the two are kept as they are; files
+    /// both leave unchanged, such as one added by this patch set and
+    /// deleted by `next`, are left out.
+    pub fn combine(&self, next: &PatchSet) -> Result<PatchSet> {
+        let mut used = vec![false; next.files.len()];
+        let mut files = vec![];
+        for file in &self.files {
+            let path = file.target_path().or_else(|| file.source_path());
+            let found =
+                next.files.iter().enumerate().position(|(i, f)| {
+                    !used[i] && f.source_path().or_else(|| f.target_path()) == path
+                });
+            let combined = match found {
+                Some(i) => {
+                    used[i] = true;
+                    let combined = file.combine(&next.files[i])?;
+                    let unchanged = combined.hunks.is_empty()
+                        && combined.properties.is_empty()
+                        && combined.source_path() == combined.target_path()
+                        && reverted_header(file, &next.files[i]);
+                    if unchanged {
+                        continue;
+                    }
+                    combined
+                }
+                None => file.clone(),
+            };
+            files.push(combined);
+        }
+        files.extend(
+            next.files
+                .iter()
+                .zip(used)
+                .filter(|&(_, used)| !used)
+                .map(|(f, _)| f.clone()),
+        );
+        Ok(self.with_files(files))
+    }
+}
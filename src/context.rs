@@ -0,0 +1,128 @@
+//! Changing the number of context lines of a patch, like `diff -U`
+
+use crate::edit::build_hunk;
+use crate::{Error, Line, LineType, PatchedFile, Result};
+
+const NO_NEWLINE: &str = " No newline at end of file";
+
+/// Split `text` into lines, telling whether the last one ends with a newline
+fn split_lines(text: &str) -> (Vec<&str>, bool) {
+    match text.strip_suffix('\n') {
+        Some(text) => (text.split('\n').collect(), true),
+        None if text.is_empty() => (vec![], true),
+        None => (text.split('\n').collect(), false),
+    }
+}
+
+/// The text git puts after `@@` for a hunk starting at source line `pos`:
+/// the closest line above it starting with a letter, `_` or `$`
+fn section_header(source: &[&str], pos: usize) -> String {
+    let above = pos.saturating_sub(1).min(source.len());
+    source[..above]
+        .iter()
+        .rev()
+        .find(|l| l.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '$'))
+        .map_or_else(String::new, |l| {
+            l.chars().take(80).collect::<String>().trim_end().to_owned()
+        })
+}
+
+impl PatchedFile {
+    /// Regenerate the hunks with `context` lines of context, taking the
+    /// lines around the changes from `source`, the original file
+    ///
+    /// Hunks grow or shrink, merge when at most `2 * context` lines apart
+    /// and split when further apart, and get a new section header. Fails
+    /// without changing the file when `source` does not match the patch.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let mut patch: PatchSet = "--- a/f\n+++ b/f\n@@ -3 +3 @@\n-c\n+C\n".parse().unwrap();
+    /// patch[0].set_context("a\nb\nc\nd\n", 1).unwrap();
+    /// assert_eq!("@@ -2,3 +2,3 @@ a\n b\n-c\n+C\n d", patch[0][0].to_string());
+    /// ```
+    pub fn set_context(&mut self, source: &str, context: usize) -> Result<()> {
+        let (source_lines, newline_at_end) = split_lines(source);
+        let context_line = |pos: usize, lines: &mut Vec<Line>| {
+            lines.push(Line::new(source_lines[pos - 1], LineType::Context));
+            if pos == source_lines.len() && !newline_at_end {
+                lines.push(Line::new(NO_NEWLINE, LineType::Empty));
+            }
+        };
+
+        // the whole file, as a single hunk
+        let mut lines: Vec<Line> = vec![];
+        let mut pos = 1;
+        for hunk in &self.hunks {
+            let (start, _) = hunk.start_positions();
+            if start < pos || start > source_lines.len() + 1 {
+                return Err(Error::SourceMismatch(hunk.to_string()));
+            }
+            for p in pos..start {
+                context_line(p, &mut lines);
+            }
+            pos = start;
+            for line in &hunk.lines {
+                if line.is_removed() || line.is_context() {
+                    if source_lines.get(pos - 1) != Some(&line.value.as_str()) {
+                        return Err(Error::SourceMismatch(line.to_string()));
+                    }
+                    pos += 1;
+                }
+                lines.push(line.clone());
+            }
+        }
+        for p in pos..=source_lines.len() {
+            context_line(p, &mut lines);
+        }
+
+        // runs of changed lines, a "no newline" marker going with the line before it
+        let mut runs: Vec<(usize, usize)> = vec![];
+        let mut changed = false;
+        for (idx, line) in lines.iter().enumerate() {
+            if line.line_type != LineType::Empty {
+                changed = line.is_added() || line.is_removed();
+            }
+            if !changed {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(run) if run.1 == idx => run.1 = idx + 1,
+                Some(run) if idx - run.1 <= 2 * context => run.1 = idx + 1,
+                _ => runs.push((idx, idx + 1)),
+            }
+        }
+
+        let mut positions = Vec::with_capacity(lines.len());
+        let (mut source_pos, mut target_pos) = (1, 1);
+        for line in &lines {
+            positions.push((source_pos, target_pos));
+            if line.is_removed() || line.is_context() {
+                source_pos += 1;
+            }
+            if line.is_added() || line.is_context() {
+                target_pos += 1;
+            }
+        }
+
+        self.hunks = runs
+            .into_iter()
+            .map(|(first, last)| {
+                let start = first.saturating_sub(context);
+                let mut end = (last + context).min(lines.len());
+                if lines.get(end).map(|l| &l.line_type) == Some(&LineType::Empty) {
+                    end += 1;
+                }
+                let (source_pos, target_pos) = positions[start];
+                build_hunk(
+                    source_pos,
+                    target_pos,
+                    &section_header(&source_lines, source_pos),
+                    lines[start..end].to_vec(),
+                )
+            })
+            .collect();
+        Ok(())
+    }
+}
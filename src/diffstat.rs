@@ -0,0 +1,349 @@
+//! `git diff --stat` style summaries
+
+use std::fmt;
+
+use crate::quote;
+use crate::{PatchSet, PatchedFile};
+
+/// Width of the `--stat` output when none is given, as for git
+const DEFAULT_WIDTH: usize = 80;
+
+/// Insertion and deletion counts of a file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileStat {
+    /// Path as git shows it, `dir/{old => new}` for renames
+    pub path: String,
+    /// Count of lines added
+    pub added: usize,
+    /// Count of lines removed
+    pub removed: usize,
+    /// Is this a binary file, whose lines are not counted
+    pub binary: bool,
+}
+
+impl FileStat {
+    /// Count of lines added and removed
+    pub fn changes(&self) -> usize {
+        self.added + self.removed
+    }
+}
+
+/// Insertion and deletion counts of the files of a patch set
+///
+/// ```
+/// use unidiff::PatchSet;
+///
+/// let patch: PatchSet = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n"
+///     .parse()
+///     .unwrap();
+/// let stat = patch.stat();
+/// assert_eq!("1\t1\tsrc/lib.rs\n", stat.numstat());
+/// assert_eq!(
+///     " src/lib.rs | 2 +-\n 1 file changed, 1 insertion(+), 1 deletion(-)\n",
+///     stat.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DiffStat {
+    /// Counts of each file, in patch order
+    pub files: Vec<FileStat>,
+}
+
+/// Show a rename the way git does, with the common directories of both
+/// paths outside braces: `src/{old => new}/lib.rs`
+fn pprint_rename(a: &str, b: &str) -> String {
+    let (a_bytes, b_bytes) = (a.as_bytes(), b.as_bytes());
+    let mut prefix = 0;
+    for (idx, (x, y)) in a_bytes.iter().zip(b_bytes).enumerate() {
+        if x != y {
+            break;
+        }
+        if *x == b'/' {
+            prefix = idx + 1;
+        }
+    }
+    // the common suffix may start at the slash ending the common prefix
+    let floor = prefix.saturating_sub(1);
+    let mut suffix = 0;
+    let (mut i, mut j) = (a_bytes.len(), b_bytes.len());
+    while i > floor && j > floor && a_bytes[i - 1] == b_bytes[j - 1] {
+        i -= 1;
+        j -= 1;
+        if a_bytes[i] == b'/' {
+            suffix = a_bytes.len() - i;
+        }
+    }
+    if prefix + suffix == 0 {
+        return format!("{} => {}", a, b);
+    }
+    let a_mid = &a[prefix..a.len().saturating_sub(suffix).max(prefix)];
+    let b_mid = &b[prefix..b.len().saturating_sub(suffix).max(prefix)];
+    format!(
+        "{}{{{} => {}}}{}",
+        &a[..prefix],
+        a_mid,
+        b_mid,
+        &a[a.len() - suffix..]
+    )
+}
+
+/// Name of `file` in stats, quoted if needed
+fn stat_name(file: &PatchedFile) -> String {
+    match (file.source_path(), file.target_path()) {
+        (Some(source), Some(target)) if source != target => {
+            pprint_rename(&quote::quote(&source), &quote::quote(&target))
+        }
+        _ => quote::quote(&file.path()),
+    }
+}
+
+impl PatchSet {
+    /// Insertion and deletion counts of each file, like `git diff --stat`
+    pub fn stat(&self) -> DiffStat {
+        DiffStat {
+            files: self
+                .files
+                .iter()
+                .map(|f| FileStat {
+                    path: stat_name(f),
+                    added: f.added(),
+                    removed: f.removed(),
+                    binary: f.is_binary(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Created, deleted and renamed files and mode changes, like
+    /// `git diff --summary`
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            let header_value = |key: &str| {
+                file.header
+                    .iter()
+                    .find_map(|l| l.strip_prefix(key))
+                    .map(str::trim)
+            };
+            if let Some(mode) = header_value("new file mode ") {
+                out.push_str(&format!(" create mode {} {}\n", mode, stat_name(file)));
+                continue;
+            }
+            if let Some(mode) = header_value("deleted file mode ") {
+                out.push_str(&format!(" delete mode {} {}\n", mode, stat_name(file)));
+                continue;
+            }
+            let copy = header_value("copy from ").is_some();
+            let renamed = copy || header_value("rename from ").is_some();
+            if renamed {
+                let similarity = header_value("similarity index ").unwrap_or("100%");
+                out.push_str(&format!(
+                    " {} {} ({})\n",
+                    if copy { "copy" } else { "rename" },
+                    stat_name(file),
+                    similarity
+                ));
+            } else if let Some(dissimilarity) = header_value("dissimilarity index ") {
+                out.push_str(&format!(
+                    " rewrite {} ({})\n",
+                    stat_name(file),
+                    dissimilarity
+                ));
+            }
+            if let (Some(old), Some(new)) = (header_value("old mode "), header_value("new mode ")) {
+                if renamed {
+                    out.push_str(&format!(" mode change {} => {}\n", old, new));
+                } else {
+                    out.push_str(&format!(
+                        " mode change {} => {} {}\n",
+                        old,
+                        new,
+                        stat_name(file)
+                    ));
+                }
+            }
+        }
+        out
+    }
+}
+
+fn decimal_width(n: usize) -> usize {
+    n.to_string().len()
+}
+
+fn scale_linear(it: usize, width: usize, max_change: usize) -> usize {
+    if it == 0 {
+        return 0;
+    }
+    // Scale as if the graph were one column shorter and add one, so every
+    // changed path gets at least one `+` or `-`.
+    1 + (it * (width - 1) / max_change)
+}
+
+/// Shorten `name` to fit `width` columns, keeping its tail
+fn truncate_name(name: &str, width: usize) -> (&'static str, &str) {
+    let len = name.chars().count();
+    if len <= width {
+        return ("", name);
+    }
+    let keep = width.saturating_sub(3);
+    let start = name
+        .char_indices()
+        .nth(len - keep)
+        .map_or(name.len(), |(i, _)| i);
+    let tail = &name[start..];
+    match tail.find('/') {
+        Some(slash) => ("...", &tail[slash..]),
+        None => ("...", tail),
+    }
+}
+
+impl DiffStat {
+    /// Count of lines added in all files
+    pub fn insertions(&self) -> usize {
+        self.files.iter().map(|s| s.added).sum()
+    }
+
+    /// Count of lines removed in all files
+    pub fn deletions(&self) -> usize {
+        self.files.iter().map(|s| s.removed).sum()
+    }
+
+    /// Render the way `git diff --stat` does for a `width` column output
+    pub fn render(&self, width: usize) -> String {
+        let max_len = self
+            .files
+            .iter()
+            .map(|s| s.path.chars().count())
+            .max()
+            .unwrap_or(0);
+        let max_change = self
+            .files
+            .iter()
+            .filter(|s| !s.binary)
+            .map(FileStat::changes)
+            .max()
+            .unwrap_or(0);
+        let mut number_width = decimal_width(max_change);
+        // binary files show `Bin` in place of the count
+        if self.files.iter().any(|s| s.binary) {
+            number_width = number_width.max(3);
+        }
+        let width = width.max(16 + 6 + number_width);
+
+        let mut graph_width = max_change;
+        let mut name_width = max_len;
+        if name_width + number_width + 6 + graph_width > width {
+            let limit = (width * 3 / 8).saturating_sub(number_width + 6);
+            if graph_width > limit {
+                graph_width = limit.max(6);
+            }
+            if name_width > width - number_width - 6 - graph_width {
+                name_width = width - number_width - 6 - graph_width;
+            } else {
+                graph_width = width - number_width - 6 - name_width;
+            }
+        }
+
+        let mut out = String::new();
+        for stat in &self.files {
+            let (prefix, name) = truncate_name(&stat.path, name_width);
+            let padding = name_width
+                .saturating_sub(prefix.len())
+                .saturating_sub(name.chars().count());
+            if stat.binary {
+                out.push_str(&format!(
+                    " {}{}{} | {:>width$}\n",
+                    prefix,
+                    name,
+                    " ".repeat(padding),
+                    "Bin",
+                    width = number_width
+                ));
+                continue;
+            }
+            let (mut add, mut del) = (stat.added, stat.removed);
+            if graph_width <= max_change {
+                let mut total = scale_linear(add + del, graph_width, max_change);
+                if total < 2 && add > 0 && del > 0 {
+                    total = 2;
+                }
+                if add < del {
+                    add = scale_linear(add, graph_width, max_change);
+                    del = total - add;
+                } else {
+                    del = scale_linear(del, graph_width, max_change);
+                    add = total - del;
+                }
+            }
+            out.push_str(&format!(
+                " {}{}{} | {:>width$}{}{}{}\n",
+                prefix,
+                name,
+                " ".repeat(padding),
+                stat.changes(),
+                if stat.changes() > 0 { " " } else { "" },
+                "+".repeat(add),
+                "-".repeat(del),
+                width = number_width
+            ));
+        }
+        out.push_str(&self.shortstat());
+        out
+    }
+
+    /// Tab separated counts and path of each file, like `git diff --numstat`
+    ///
+    /// Binary files show `-` for both counts.
+    pub fn numstat(&self) -> String {
+        let mut out = String::new();
+        for stat in &self.files {
+            if stat.binary {
+                out.push_str(&format!("-\t-\t{}\n", stat.path));
+            } else {
+                out.push_str(&format!(
+                    "{}\t{}\t{}\n",
+                    stat.added, stat.removed, stat.path
+                ));
+            }
+        }
+        out
+    }
+
+    /// The `N files changed, ...` line, like `git diff --shortstat`
+    pub fn shortstat(&self) -> String {
+        let files = self.files.len();
+        if files == 0 {
+            return " 0 files changed\n".to_owned();
+        }
+        let insertions = self.insertions();
+        let deletions = self.deletions();
+        let mut out = format!(
+            " {} file{} changed",
+            files,
+            if files == 1 { "" } else { "s" }
+        );
+        if insertions > 0 || deletions == 0 {
+            out.push_str(&format!(
+                ", {} insertion{}(+)",
+                insertions,
+                if insertions == 1 { "" } else { "s" }
+            ));
+        }
+        if deletions > 0 || insertions == 0 {
+            out.push_str(&format!(
+                ", {} deletion{}(-)",
+                deletions,
+                if deletions == 1 { "" } else { "s" }
+            ));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+impl fmt::Display for DiffStat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(DEFAULT_WIDTH))
+    }
+}
@@ -0,0 +1,232 @@
+//! Editing hunks: splitting, merging, selecting lines and recounting
+
+use crate::{Hunk, Line, LineType, PatchSet, PatchedFile};
+
+/// Build a hunk from `lines`, the first of which sits at `source_pos` and
+/// `target_pos`, numbering the lines and computing the header
+pub(crate) fn build_hunk(
+    source_pos: usize,
+    target_pos: usize,
+    section_header: &str,
+    lines: Vec<Line>,
+) -> Hunk {
+    let mut hunk = Hunk::new(source_pos, 0, target_pos, 0, section_header);
+    let (mut source_line_no, mut target_line_no) = (source_pos, target_pos);
+    for mut line in lines {
+        line.source_line_no = None;
+        line.target_line_no = None;
+        if line.is_removed() || line.is_context() {
+            line.source_line_no = Some(source_line_no);
+            source_line_no += 1;
+            hunk.source_length += 1;
+        }
+        if line.is_added() || line.is_context() {
+            line.target_line_no = Some(target_line_no);
+            target_line_no += 1;
+            hunk.target_length += 1;
+        }
+        hunk.append(line);
+    }
+    // an empty range starts at the line before it
+    if hunk.source_length == 0 {
+        hunk.source_start = source_pos.saturating_sub(1);
+    }
+    if hunk.target_length == 0 {
+        hunk.target_start = target_pos.saturating_sub(1);
+    }
+    hunk
+}
+
+impl Hunk {
+    /// Position of the first source and target line of this hunk
+    pub(crate) fn start_positions(&self) -> (usize, usize) {
+        let source = if self.source_length == 0 {
+            self.source_start + 1
+        } else {
+            self.source_start
+        };
+        let target = if self.target_length == 0 {
+            self.target_start + 1
+        } else {
+            self.target_start
+        };
+        (source, target)
+    }
+
+    /// Source and target position of each line, and past the last one
+    pub(crate) fn line_positions(&self) -> Vec<(usize, usize)> {
+        let (mut source, mut target) = self.start_positions();
+        let mut positions = Vec::with_capacity(self.lines.len() + 1);
+        for line in &self.lines {
+            positions.push((source, target));
+            match line.line_type {
+                LineType::Added => target += 1,
+                LineType::Removed => source += 1,
+                LineType::Context => {
+                    source += 1;
+                    target += 1;
+                }
+                LineType::Empty => {}
+            }
+        }
+        positions.push((source, target));
+        positions
+    }
+
+    /// Split into the smallest hunks separated by context, like the `s`
+    /// command of `git add -p`
+    ///
+    /// Context between two changes is kept in both of the hunks around it.
+    pub fn split(&self) -> Vec<Hunk> {
+        // runs of changed lines, a "no newline" marker going with the line before it
+        let mut runs: Vec<(usize, usize)> = vec![];
+        let mut changed = false;
+        for (idx, line) in self.lines.iter().enumerate() {
+            if line.line_type != LineType::Empty {
+                changed = line.is_added() || line.is_removed();
+            }
+            if !changed {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(run) if run.1 == idx => run.1 = idx + 1,
+                _ => runs.push((idx, idx + 1)),
+            }
+        }
+        if runs.len() < 2 {
+            return vec![self.clone()];
+        }
+        let positions = self.line_positions();
+        (0..runs.len())
+            .map(|i| {
+                let start = if i == 0 { 0 } else { runs[i - 1].1 };
+                let end = runs.get(i + 1).map_or(self.lines.len(), |next| next.0);
+                let (source_pos, target_pos) = positions[start];
+                build_hunk(
+                    source_pos,
+                    target_pos,
+                    &self.section_header,
+                    self.lines[start..end].to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    /// Keep only the changed lines at `indexes` into [`lines`](Hunk::lines),
+    /// for staging part of a hunk
+    ///
+    /// Removed lines left out become context and added lines left out are
+    /// dropped; indexes of context lines are ignored.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n-b\n-c\n+B\n+C\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let hunk = patch[0][0].select(&[1, 3]);
+    /// assert_eq!(" a\n-b\n c\n+B", hunk.to_string().split_once('\n').unwrap().1);
+    /// assert_eq!((3, 3), (hunk.source_length, hunk.target_length));
+    /// ```
+    pub fn select(&self, indexes: &[usize]) -> Hunk {
+        let (source_pos, target_pos) = self.start_positions();
+        let mut lines = Vec::with_capacity(self.lines.len());
+        let mut keep = true;
+        for (idx, line) in self.lines.iter().enumerate() {
+            // a "no newline" marker goes with the line before it
+            if line.line_type == LineType::Empty {
+                if keep {
+                    lines.push(line.clone());
+                }
+                continue;
+            }
+            let selected = indexes.contains(&idx);
+            keep = !line.is_added() || selected;
+            if keep {
+                let mut line = line.clone();
+                if line.is_removed() && !selected {
+                    line.line_type = LineType::Context;
+                }
+                lines.push(line);
+            }
+        }
+        build_hunk(source_pos, target_pos, &self.section_header, lines)
+    }
+
+    /// Recompute the added and removed counts, the header lengths and the
+    /// line numbers from the lines, like `recountdiff`
+    ///
+    /// Use this after editing lines through [`lines_mut`](Hunk::lines_mut).
+    pub fn recount(&mut self) {
+        let (source_pos, target_pos) = self.start_positions();
+        let lines = std::mem::take(&mut self.lines);
+        *self = build_hunk(source_pos, target_pos, &self.section_header, lines);
+    }
+
+    /// Merge `next` into this hunk when their source ranges overlap or
+    /// touch and any overlapping lines are context in both
+    fn merge(&self, next: &Hunk) -> Option<Hunk> {
+        let (source_pos, target_pos) = self.start_positions();
+        let (next_source_pos, next_target_pos) = next.start_positions();
+        let source_end = source_pos + self.source_length;
+        let target_end = target_pos + self.target_length;
+        if source_end < next_source_pos {
+            return None;
+        }
+        let overlap = source_end - next_source_pos;
+        if target_end < next_target_pos || target_end - next_target_pos != overlap {
+            return None;
+        }
+        let tail_is_context = self.lines.iter().rev().take(overlap).all(Line::is_context);
+        let head_is_context = next.lines.iter().take(overlap).all(Line::is_context);
+        if self.lines.len() < overlap || !tail_is_context || !head_is_context {
+            return None;
+        }
+        let mut lines = self.lines.clone();
+        // a hunk within this one has nothing left to add
+        lines.extend(next.lines.iter().skip(overlap).cloned());
+        Some(build_hunk(
+            source_pos,
+            target_pos,
+            &self.section_header,
+            lines,
+        ))
+    }
+}
+
+impl PatchedFile {
+    /// Merge hunks whose context overlaps or touches the next hunk
+    pub fn merge_adjacent_hunks(&mut self) {
+        let mut merged: Vec<Hunk> = Vec::with_capacity(self.hunks.len());
+        for hunk in self.hunks.drain(..) {
+            let combined = merged.last().and_then(|last| last.merge(&hunk));
+            match combined {
+                Some(combined) => *merged.last_mut().unwrap() = combined,
+                None => merged.push(hunk),
+            }
+        }
+        self.hunks = merged;
+    }
+
+    /// Recount every hunk and fix the target start of each hunk from the
+    /// lines added and removed by the hunks before it
+    pub fn recount(&mut self) {
+        let mut offset: isize = 0;
+        for hunk in &mut self.hunks {
+            let (source_pos, _) = hunk.start_positions();
+            let target_pos = (source_pos as isize + offset) as usize;
+            let lines = std::mem::take(&mut hunk.lines);
+            *hunk = build_hunk(source_pos, target_pos, &hunk.section_header, lines);
+            offset += hunk.target_length as isize - hunk.source_length as isize;
+        }
+    }
+}
+
+impl PatchSet {
+    /// Recount every file, see [`PatchedFile::recount`]
+    pub fn recount(&mut self) {
+        for file in &mut self.files {
+            file.recount();
+        }
+    }
+}
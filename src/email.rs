@@ -0,0 +1,220 @@
+//! `git format-patch` compatible emails
+
+use std::fmt;
+
+use crate::quote;
+use crate::{PatchSet, PatchedFile, VcsHeader, DEV_NULL};
+
+/// Commit id used in the mbox `From` line when none is given
+const ZERO_COMMIT: &str = "0000000000000000000000000000000000000000";
+/// Line width of the diffstat section, as used by `git format-patch`
+const MAIL_DEFAULT_WRAP: usize = 72;
+/// Longest line of a header folded into RFC 2047 encoded words
+const MAX_ENCODED_LENGTH: usize = 76;
+
+/// A single patch email, as produced by `git format-patch`
+///
+/// Render it with ``to_string()``.
+///
+/// ```
+/// use unidiff::{PatchEmail, PatchSet};
+///
+/// let patch: PatchSet = "--- a/file\n+++ b/file\n@@ -1 +1 @@\n-old\n+new\n".parse().unwrap();
+/// let email = PatchEmail::new(
+///     "A U Thor <author@example.com>",
+///     "Thu, 7 Apr 2005 15:13:13 -0700",
+///     "Update file",
+///     patch,
+/// );
+/// assert!(email.to_string().contains("Subject: [PATCH] Update file\n"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PatchEmail {
+    /// Commit id for the mbox `From` line
+    pub commit: Option<String>,
+    /// Author, e.g. `A U Thor <author@example.com>`
+    pub author: String,
+    /// RFC 2822 date
+    pub date: String,
+    /// Subject, without the `[PATCH]` prefix
+    pub subject: String,
+    /// Commit message body
+    pub message: String,
+    /// Position of this patch in its series, starting at 1
+    pub number: usize,
+    /// Count of patches in the series
+    pub total: usize,
+    /// Text after the `-- ` signature separator
+    pub signature: String,
+    /// The changes
+    pub patch: PatchSet,
+}
+
+impl PatchEmail {
+    /// Initialize a new single patch email
+    pub fn new<T: Into<String>>(author: T, date: T, subject: T, patch: PatchSet) -> PatchEmail {
+        PatchEmail {
+            commit: None,
+            author: author.into(),
+            date: date.into(),
+            subject: subject.into(),
+            message: String::new(),
+            number: 1,
+            total: 1,
+            signature: concat!("unidiff ", env!("CARGO_PKG_VERSION")).to_owned(),
+            patch,
+        }
+    }
+
+    /// Subject prefix, e.g. `[PATCH]` or `[PATCH 02/12]`
+    pub fn subject_prefix(&self) -> String {
+        if self.total <= 1 {
+            return "[PATCH]".to_owned();
+        }
+        let width = self.total.to_string().len();
+        format!(
+            "[PATCH {:0width$}/{}]",
+            self.number,
+            self.total,
+            width = width
+        )
+    }
+}
+
+/// `file` with the header and names git would write for it, so that
+/// `git am` creates, deletes and renames files from other VCS diffs
+fn as_git_file(file: &PatchedFile) -> PatchedFile {
+    let (added, removed) = (file.is_added_file(), file.is_removed_file());
+    let (source, target) = (file.source_path(), file.target_path());
+    // git names both sides after the file for additions and deletions
+    let source = source.or_else(|| target.clone()).unwrap_or_default();
+    let target = target.unwrap_or_else(|| source.clone());
+    let quoted = file.source_quoted || file.target_quoted;
+    let mut file = file.clone();
+    file.header = vec![format!(
+        "diff --git {} {}",
+        quote::requote(&format!("a/{}", source), quoted),
+        quote::requote(&format!("b/{}", target), quoted)
+    )];
+    file.source_file = format!("a/{}", source);
+    file.target_file = format!("b/{}", target);
+    file.source_quoted = quoted;
+    file.target_quoted = quoted;
+    if added {
+        file.header.push("new file mode 100644".to_owned());
+        file.source_file = DEV_NULL.to_owned();
+    } else if removed {
+        file.header.push("deleted file mode 100644".to_owned());
+        file.target_file = DEV_NULL.to_owned();
+    } else if source != target {
+        file.header
+            .push(format!("rename from {}", quote::requote(&source, quoted)));
+        file.header
+            .push(format!("rename to {}", quote::requote(&target, quoted)));
+    }
+    file
+}
+
+/// `text` as RFC 2047 encoded words when it is not plain ASCII, for a
+/// header whose line already holds `line_len` characters
+///
+/// Like `git format-patch`, spaces are written `=20` and, in an address,
+/// everything but letters, digits and `!*+-/` is encoded.
+fn encode_header(text: &str, mut line_len: usize, address: bool) -> String {
+    const START: &str = "=?UTF-8?q?";
+    if text.is_ascii() {
+        return text.to_owned();
+    }
+    let mut encoded = START.to_owned();
+    line_len += START.len();
+    for c in text.chars() {
+        let special = !c.is_ascii()
+            || matches!(c, '=' | '?' | '_' | ' ')
+            || (address && !c.is_ascii_alphanumeric() && !"!*+-/".contains(c));
+        let chunk = if special {
+            let mut buf = [0; 4];
+            c.encode_utf8(&mut buf)
+                .bytes()
+                .map(|b| format!("={:02X}", b))
+                .collect()
+        } else {
+            c.to_string()
+        };
+        // fold before the line outgrows the limit, counting the closing `?=`
+        if line_len + chunk.len() + 2 > MAX_ENCODED_LENGTH {
+            encoded.push_str("?=\n ");
+            encoded.push_str(START);
+            line_len = 1 + START.len();
+        }
+        encoded.push_str(&chunk);
+        line_len += chunk.len();
+    }
+    encoded.push_str("?=");
+    encoded
+}
+
+/// `author` with its name RFC 2047 encoded, for the `From` header
+fn encode_author(author: &str) -> String {
+    const FROM: &str = "From: ";
+    match author.rsplit_once(" <") {
+        Some((name, address)) => format!("{} <{}", encode_header(name, FROM.len(), true), address),
+        None => encode_header(author, FROM.len(), true),
+    }
+}
+
+impl fmt::Display for PatchEmail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "From {} Mon Sep 17 00:00:00 2001",
+            self.commit.as_deref().unwrap_or(ZERO_COMMIT)
+        )?;
+        writeln!(f, "From: {}", encode_author(&self.author))?;
+        writeln!(f, "Date: {}", self.date)?;
+        let subject = format!("Subject: {} ", self.subject_prefix());
+        writeln!(
+            f,
+            "{}{}",
+            subject,
+            encode_header(&self.subject, subject.len(), false)
+        )?;
+        if !(self.author.is_ascii() && self.subject.is_ascii() && self.message.is_ascii()) {
+            writeln!(f, "MIME-Version: 1.0")?;
+            writeln!(f, "Content-Type: text/plain; charset=UTF-8")?;
+            writeln!(f, "Content-Transfer-Encoding: 8bit")?;
+        }
+        writeln!(f)?;
+        let message = self.message.trim_end();
+        if !message.is_empty() {
+            writeln!(f, "{}", message)?;
+        }
+        writeln!(f, "---")?;
+        write!(f, "{}", self.patch.stat().render(MAIL_DEFAULT_WRAP))?;
+        write!(f, "{}", self.patch.summary())?;
+        writeln!(f)?;
+        for file in self.patch.files() {
+            if let Some(VcsHeader::Git { .. }) = file.vcs_header() {
+                writeln!(f, "{}", file)?;
+            } else {
+                writeln!(f, "{}", as_git_file(file))?;
+            }
+        }
+        writeln!(f, "-- ")?;
+        writeln!(f, "{}", self.signature)?;
+        writeln!(f)
+    }
+}
+
+/// Number `emails` as a patch series and render each one
+pub fn format_series(emails: &mut [PatchEmail]) -> Vec<String> {
+    let total = emails.len();
+    emails
+        .iter_mut()
+        .enumerate()
+        .map(|(i, email)| {
+            email.number = i + 1;
+            email.total = total;
+            email.to_string()
+        })
+        .collect()
+}
@@ -0,0 +1,185 @@
+//! Selecting files of a patch set, like `filterdiff`
+
+use regex::Regex;
+
+use crate::{PatchSet, PatchedFile};
+
+/// A glob pattern for matching file paths, like `src/**/*.rs`
+///
+/// `*` and `?` do not match `/`, `**` matches across directories and
+/// `[...]` matches a character class. Patterns without a `/` match the
+/// file name in any directory, as in `.gitignore`.
+///
+/// ```
+/// use unidiff::Glob;
+///
+/// let glob = Glob::new("src/**/*.rs");
+/// assert!(glob.is_match("src/lib.rs"));
+/// assert!(glob.is_match("src/a/b/mod.rs"));
+/// assert!(!glob.is_match("tests/lib.rs"));
+/// assert!(Glob::new("*.rs").is_match("src/lib.rs"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: String,
+    regex: Regex,
+}
+
+impl Glob {
+    /// Compile a glob pattern
+    pub fn new<T: AsRef<str>>(pattern: T) -> Glob {
+        let pattern = pattern.as_ref();
+        // a malformed class such as `[z-a]` is matched literally
+        let regex = Regex::new(&glob_to_regex(pattern))
+            .unwrap_or_else(|_| Regex::new(&format!("^{}$", regex::escape(pattern))).unwrap());
+        Glob {
+            pattern: pattern.to_owned(),
+            regex,
+        }
+    }
+
+    /// The pattern this glob was compiled from
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Does `path` match this glob
+    pub fn is_match<T: AsRef<str>>(&self, path: T) -> bool {
+        self.regex.is_match(path.as_ref())
+    }
+
+    /// Does the source or the target path of `file` match this glob
+    pub fn matches_file(&self, file: &PatchedFile) -> bool {
+        file.source_path()
+            .into_iter()
+            .chain(file.target_path())
+            .any(|p| self.is_match(p))
+    }
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    let pattern = pattern.strip_prefix('/').unwrap_or_else(|| {
+        if !pattern.contains('/') {
+            regex.push_str("(?:.*/)?");
+        }
+        pattern
+    });
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut idx = 0;
+    while idx < chars.len() {
+        match chars[idx] {
+            '*' if chars.get(idx + 1) == Some(&'*') => {
+                let at_start = idx == 0 || chars[idx - 1] == '/';
+                if at_start && chars.get(idx + 2) == Some(&'/') {
+                    regex.push_str("(?:.*/)?");
+                    idx += 3;
+                } else {
+                    regex.push_str(".*");
+                    idx += 2;
+                }
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let negated = matches!(chars.get(idx + 1), Some('!') | Some('^'));
+                let start = idx + 1 + negated as usize;
+                // a `]` right after the bracket, or after its `!`, is part of
+                // the class
+                let len = match chars
+                    .get(start + 1..)
+                    .and_then(|rest| rest.iter().position(|&c| c == ']'))
+                {
+                    Some(len) => len + 1,
+                    None => {
+                        regex.push_str(r"\[");
+                        idx += 1;
+                        continue;
+                    }
+                };
+                regex.push('[');
+                if negated {
+                    regex.push('^');
+                }
+                for &c in &chars[start..start + len] {
+                    match c {
+                        '\\' | '[' | ']' | '&' | '~' => {
+                            regex.push('\\');
+                            regex.push(c);
+                        }
+                        _ => regex.push(c),
+                    }
+                }
+                regex.push(']');
+                idx = start + len + 1;
+                continue;
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        idx += 1;
+    }
+    regex.push('$');
+    regex
+}
+
+impl PatchSet {
+    /// Files matching `predicate`, as a new patch set
+    pub fn filter<F>(&self, mut predicate: F) -> PatchSet
+    where
+        F: FnMut(&PatchedFile) -> bool,
+    {
+        let files = self
+            .files
+            .iter()
+            .filter(|f| predicate(f))
+            .cloned()
+            .collect();
+        self.with_files(files)
+    }
+
+    /// Files matching any of the glob `patterns`, like `filterdiff -i`
+    ///
+    /// Patterns starting with `!` exclude files instead; when there are
+    /// only exclusions every other file is kept.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n\
+    ///     --- a/vendor/dep.rs\n+++ b/vendor/dep.rs\n@@ -1 +1 @@\n-a\n+b\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let ours = patch.include(["**/*.rs", "!vendor/**"]);
+    /// assert_eq!(1, ours.len());
+    /// assert_eq!("src/lib.rs", ours[0].path());
+    /// ```
+    pub fn include<I, T>(&self, patterns: I) -> PatchSet
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let mut includes = vec![];
+        let mut excludes = vec![];
+        for pattern in patterns {
+            match pattern.as_ref().strip_prefix('!') {
+                Some(pattern) => excludes.push(Glob::new(pattern)),
+                None => includes.push(Glob::new(pattern)),
+            }
+        }
+        self.filter(|f| {
+            (includes.is_empty() || includes.iter().any(|g| g.matches_file(f)))
+                && !excludes.iter().any(|g| g.matches_file(f))
+        })
+    }
+
+    /// Files matching none of the glob `patterns`, like `filterdiff -x`
+    pub fn exclude<I, T>(&self, patterns: I) -> PatchSet
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let excludes: Vec<Glob> = patterns.into_iter().map(Glob::new).collect();
+        self.filter(|f| !excludes.iter().any(|g| g.matches_file(f)))
+    }
+}
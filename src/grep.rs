@@ -0,0 +1,117 @@
+//! Searching lines of a patch set, like `grepdiff`
+
+use regex::Regex;
+
+use crate::{Hunk, Line, LineType, PatchSet, PatchedFile};
+
+/// A line found by a search, with its position in the patch set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch<'a> {
+    /// Path of the file the line belongs to
+    pub path: String,
+    /// Index of the file in the patch set
+    pub file_index: usize,
+    /// Index of the hunk in the file
+    pub hunk_index: usize,
+    /// Index of the line in the hunk
+    pub line_index: usize,
+    /// The line itself, with its source, target and diff line numbers
+    pub line: &'a Line,
+}
+
+fn grep_line(regex: &Regex, line_types: &[LineType], line: &Line) -> bool {
+    line_types.contains(&line.line_type) && regex.is_match(&line.value)
+}
+
+impl PatchSet {
+    /// Lines matching `predicate`
+    pub fn find_lines<F>(&self, mut predicate: F) -> Vec<LineMatch<'_>>
+    where
+        F: FnMut(&Line) -> bool,
+    {
+        let mut matches = vec![];
+        for (file_index, file) in self.files.iter().enumerate() {
+            for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+                for (line_index, line) in hunk.lines.iter().enumerate() {
+                    if predicate(line) {
+                        matches.push(LineMatch {
+                            path: file.path(),
+                            file_index,
+                            hunk_index,
+                            line_index,
+                            line,
+                        });
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// Lines of one of `line_types` whose value matches `regex`
+    ///
+    /// ```
+    /// use regex::Regex;
+    /// use unidiff::{LineType, PatchSet};
+    ///
+    /// let patch: PatchSet = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n \
+    ///     fn run() {\n-    safe();\n+    unsafe { fast() };\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let found = patch.grep(&Regex::new(r"\bunsafe\b").unwrap(), &[LineType::Added]);
+    /// assert_eq!(1, found.len());
+    /// assert_eq!("src/lib.rs", found[0].path);
+    /// assert_eq!(Some(2), found[0].line.target_line_no);
+    /// ```
+    pub fn grep(&self, regex: &Regex, line_types: &[LineType]) -> Vec<LineMatch<'_>> {
+        self.find_lines(|l| grep_line(regex, line_types, l))
+    }
+
+    /// Only the hunks matching `predicate`, dropping files left without
+    /// hunks
+    ///
+    /// Hunk headers are kept as they are, so later hunks of a file may be
+    /// offset by the hunks left out; [`recount`](PatchSet::recount) fixes
+    /// them.
+    pub fn filter_hunks<F>(&self, mut predicate: F) -> PatchSet
+    where
+        F: FnMut(&PatchedFile, &Hunk) -> bool,
+    {
+        let files = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                let hunks: Vec<Hunk> = file
+                    .hunks
+                    .iter()
+                    .filter(|h| predicate(file, h))
+                    .cloned()
+                    .collect();
+                if hunks.is_empty() {
+                    return None;
+                }
+                let mut file = file.clone();
+                file.hunks = hunks;
+                Some(file)
+            })
+            .collect();
+        self.with_files(files)
+    }
+
+    /// Only the hunks with a line of one of `line_types` whose value matches
+    /// `regex`, like `grepdiff --output-matching=hunk`
+    pub fn grep_hunks(&self, regex: &Regex, line_types: &[LineType]) -> PatchSet {
+        self.filter_hunks(|_, hunk| hunk.lines.iter().any(|l| grep_line(regex, line_types, l)))
+    }
+
+    /// Only the files with a line of one of `line_types` whose value
+    /// matches `regex`, like `grepdiff --output-matching=file`
+    pub fn grep_files(&self, regex: &Regex, line_types: &[LineType]) -> PatchSet {
+        self.filter(|file| {
+            file.hunks
+                .iter()
+                .flat_map(|h| h.lines.iter())
+                .any(|l| grep_line(regex, line_types, l))
+        })
+    }
+}
@@ -0,0 +1,141 @@
+//! Changes within lines, for highlighting the words a change touched
+
+use std::ops::Range;
+
+use crate::myers::{self, Edit};
+use crate::{Hunk, Line};
+
+/// Unit intra-line changes are computed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Granularity {
+    /// Runs of letters, digits and `_`, runs of whitespace and single
+    /// punctuation characters
+    Word,
+    /// Single characters
+    Char,
+}
+
+/// Byte ranges of the tokens of `value`
+fn tokenize(value: &str, granularity: Granularity) -> Vec<Range<usize>> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            1
+        } else if c.is_whitespace() {
+            2
+        } else {
+            0
+        }
+    };
+    let mut tokens: Vec<Range<usize>> = vec![];
+    let mut previous = None;
+    for (idx, c) in value.char_indices() {
+        let end = idx + c.len_utf8();
+        let this = class(c);
+        match tokens.last_mut() {
+            Some(last)
+                if granularity == Granularity::Word && this != 0 && previous == Some(this) =>
+            {
+                last.end = end;
+            }
+            _ => tokens.push(idx..end),
+        }
+        previous = Some(this);
+    }
+    tokens
+}
+
+/// Append `range` to `ranges`, joining it to the last one when they touch
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+impl Line {
+    /// Byte ranges of this line's value and of `other`'s that differ
+    /// between both
+    ///
+    /// ```
+    /// use unidiff::{Granularity, Line, LineType};
+    ///
+    /// let old = Line::new("let x = 1;", LineType::Removed);
+    /// let new = Line::new("let y = 1;", LineType::Added);
+    /// assert_eq!((vec![4..5], vec![4..5]), old.changed_ranges(&new, Granularity::Word));
+    /// ```
+    pub fn changed_ranges(
+        &self,
+        other: &Line,
+        granularity: Granularity,
+    ) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+        let old_tokens = tokenize(&self.value, granularity);
+        let new_tokens = tokenize(&other.value, granularity);
+        let old_words: Vec<&str> = old_tokens.iter().map(|r| &self.value[r.clone()]).collect();
+        let new_words: Vec<&str> = new_tokens.iter().map(|r| &other.value[r.clone()]).collect();
+        let (mut old_ranges, mut new_ranges) = (vec![], vec![]);
+        for edit in myers::diff(&old_words, &new_words) {
+            match edit {
+                Edit::Equal(..) => {}
+                Edit::Delete(x) => push_range(&mut old_ranges, old_tokens[x].clone()),
+                Edit::Insert(y) => push_range(&mut new_ranges, new_tokens[y].clone()),
+            }
+        }
+        (old_ranges, new_ranges)
+    }
+}
+
+impl Hunk {
+    /// Each line of the hunk with the byte ranges that changed within it,
+    /// compared to the line opposite it in the same block of changes
+    ///
+    /// The n-th removed line of a block of changes is paired with its n-th
+    /// added line. Context lines and changed lines without a counterpart
+    /// have no ranges.
+    ///
+    /// ```
+    /// use unidiff::{Granularity, PatchSet};
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-fn old()\n+fn new()\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// for (line, ranges) in patch[0][0].inline_changes(Granularity::Word) {
+    ///     match ranges {
+    ///         Some(ranges) => assert_eq!(vec![3..6], ranges),
+    ///         None => assert!(line.is_context()),
+    ///     }
+    /// }
+    /// ```
+    pub fn inline_changes(
+        &self,
+        granularity: Granularity,
+    ) -> Vec<(&Line, Option<Vec<Range<usize>>>)> {
+        let mut changes: Vec<_> = self.lines.iter().map(|l| (l, None)).collect();
+        let mut removed = vec![];
+        let mut added = vec![];
+        // a trailing context line ends the last block
+        for idx in 0..=self.lines.len() {
+            match self.lines.get(idx) {
+                Some(line) if line.is_removed() => {
+                    removed.push(idx);
+                    continue;
+                }
+                Some(line) if line.is_added() => {
+                    added.push(idx);
+                    continue;
+                }
+                // "no newline" markers belong to the line before them
+                Some(line) if !line.is_context() => continue,
+                _ => {}
+            }
+            for (&old, &new) in removed.iter().zip(&added) {
+                let (old_ranges, new_ranges) =
+                    self.lines[old].changed_ranges(&self.lines[new], granularity);
+                changes[old].1 = Some(old_ranges);
+                changes[new].1 = Some(new_ranges);
+            }
+            removed.clear();
+            added.clear();
+        }
+        changes
+    }
+}
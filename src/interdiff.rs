@@ -0,0 +1,201 @@
+//! Comparing two versions of a patch, like `interdiff` and `git range-diff`
+
+use std::fmt;
+
+use crate::myers::{self, Edit};
+use crate::{Commit, PatchSet, Result};
+
+/// Pairs of commits whose diff of diffs is at most this percentage of their
+/// size are matched, as with `git range-diff --creation-factor`
+const CREATION_FACTOR: usize = 60;
+
+impl PatchSet {
+    /// The changes between the trees this patch set and `other` produce,
+    /// both made against the same base, like `interdiff`
+    ///
+    /// Fails when the hunks of both patch sets do not agree on the base.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let v1: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n".parse().unwrap();
+    /// let v2: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+d\n".parse().unwrap();
+    /// let changes = v1.interdiff(&v2).unwrap();
+    /// assert_eq!("@@ -1,2 +1,2 @@ \n a\n-c\n+d", changes[0][0].to_string());
+    /// ```
+    pub fn interdiff(&self, other: &PatchSet) -> Result<PatchSet> {
+        self.reverse().combine(other)
+    }
+}
+
+/// The text of a commit `git range-diff` compares: its message and its
+/// changes, without line numbers
+fn range_diff_text(commit: &Commit) -> Vec<String> {
+    let mut text: Vec<String> = commit
+        .message
+        .lines()
+        .map(|l| format!("    {}", l).trim_end().to_owned())
+        .collect();
+    for file in commit.patch.files() {
+        text.push(String::new());
+        text.push(format!("## {} ##", file.path()));
+        for hunk in file.hunks() {
+            text.push(format!("@@ {}", hunk.section_header).trim_end().to_owned());
+            text.extend(hunk.lines().iter().map(|l| l.to_string()));
+        }
+    }
+    text
+}
+
+/// Lines of context kept around changes between two commits
+const CONTEXT: usize = 3;
+
+/// Diff `old` and `new` with a few lines of context, starting each group of
+/// changes with a `@@` line
+fn diff_text(old: &[String], new: &[String]) -> Vec<String> {
+    let edits = myers::diff(old, new);
+    let mut diff = vec![];
+    let mut shown = 0;
+    for idx in (0..edits.len()).filter(|&idx| !matches!(edits[idx], Edit::Equal(..))) {
+        let start = idx.saturating_sub(CONTEXT).max(shown);
+        if start > shown || shown == 0 {
+            diff.push("@@".to_owned());
+        }
+        let end = (idx + CONTEXT + 1).min(edits.len());
+        for edit in &edits[start..end] {
+            diff.push(match *edit {
+                Edit::Equal(x, _) => format!(" {}", old[x]),
+                Edit::Delete(x) => format!("-{}", old[x]),
+                Edit::Insert(y) => format!("+{}", new[y]),
+            });
+        }
+        shown = end;
+    }
+    diff
+}
+
+/// A commit of the old series paired with one of the new series, or either
+/// one left unmatched
+#[derive(Debug, Clone)]
+pub struct RangeDiffEntry<'a> {
+    /// Index and commit in the old series
+    pub old: Option<(usize, &'a Commit)>,
+    /// Index and commit in the new series
+    pub new: Option<(usize, &'a Commit)>,
+    /// Diff between the messages and changes of both commits, one line each
+    /// prefixed by ` `, `-` or `+`, empty when they are the same
+    pub diff: Vec<String>,
+}
+
+impl<'a> RangeDiffEntry<'a> {
+    /// `=` for identical commits, `!` for changed ones, `<` for commits
+    /// only in the old series and `>` for commits only in the new one
+    pub fn status(&self) -> char {
+        match (self.old, self.new) {
+            (Some(_), None) => '<',
+            (None, Some(_)) => '>',
+            _ if self.diff.is_empty() => '=',
+            _ => '!',
+        }
+    }
+}
+
+impl<'a> fmt::Display for RangeDiffEntry<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = |commit: Option<(usize, &Commit)>| match commit {
+            Some((idx, commit)) => {
+                format!(
+                    "{}:  {}",
+                    idx + 1,
+                    commit.id.chars().take(7).collect::<String>()
+                )
+            }
+            None => "-:  -------".to_owned(),
+        };
+        let subject = self.new.or(self.old).map_or("", |(_, c)| c.subject());
+        write!(
+            f,
+            "{} {} {} {}",
+            side(self.old),
+            self.status(),
+            side(self.new),
+            subject
+        )?;
+        for line in &self.diff {
+            write!(f, "\n    {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Match the commits of two versions of a patch series and compare them,
+/// like `git range-diff`
+///
+/// Commits are paired greedily, most similar first. Entries follow the new
+/// series, with each old commit left unmatched listed right after the old
+/// commits before it.
+pub fn range_diff<'a>(old: &'a [Commit], new: &'a [Commit]) -> Vec<RangeDiffEntry<'a>> {
+    let old_text: Vec<Vec<String>> = old.iter().map(range_diff_text).collect();
+    let new_text: Vec<Vec<String>> = new.iter().map(range_diff_text).collect();
+
+    let mut candidates = vec![];
+    for (i, a) in old_text.iter().enumerate() {
+        for (j, b) in new_text.iter().enumerate() {
+            let cost = myers::diff(a, b)
+                .iter()
+                .filter(|e| !matches!(e, Edit::Equal(..)))
+                .count();
+            if cost * 100 <= CREATION_FACTOR * a.len().max(b.len()) {
+                candidates.push((cost, i, j));
+            }
+        }
+    }
+    candidates.sort_unstable();
+    let mut old_match = vec![None; old.len()];
+    let mut new_match = vec![None; new.len()];
+    for (_, i, j) in candidates {
+        if old_match[i].is_none() && new_match[j].is_none() {
+            old_match[i] = Some(j);
+            new_match[j] = Some(i);
+        }
+    }
+
+    // the order of `git range-diff`: an unmatched old commit comes as soon
+    // as the old commits before it are shown, then the unmatched new commits
+    // up to the next pair
+    let mut entries = vec![];
+    let mut shown = vec![false; old.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        while i < old.len() && shown[i] {
+            i += 1;
+        }
+        if i < old.len() && old_match[i].is_none() {
+            entries.push(RangeDiffEntry {
+                old: Some((i, &old[i])),
+                new: None,
+                diff: vec![],
+            });
+            i += 1;
+            continue;
+        }
+        while j < new.len() && new_match[j].is_none() {
+            entries.push(RangeDiffEntry {
+                old: None,
+                new: Some((j, &new[j])),
+                diff: vec![],
+            });
+            j += 1;
+        }
+        if let Some(k) = new_match.get(j).copied().flatten() {
+            entries.push(RangeDiffEntry {
+                old: Some((k, &old[k])),
+                new: Some((j, &new[j])),
+                diff: diff_text(&old_text[k], &new_text[j]),
+            });
+            shown[k] = true;
+            j += 1;
+        }
+    }
+    entries
+}
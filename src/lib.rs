@@ -30,26 +30,76 @@ use lazy_static::lazy_static;
 
 use std::error;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 use std::str::FromStr;
 
-use regex::Regex;
+use regex::{Captures, Regex};
+
+mod combine;
+mod context;
+mod diffstat;
+mod edit;
+mod email;
+mod filter;
+mod grep;
+mod inline;
+mod interdiff;
+mod log;
+mod mapping;
+mod moves;
+mod myers;
+mod normalize;
+mod patchid;
+mod quote;
+mod rebase;
+mod reverse;
+mod review;
+mod sha1;
+mod stats;
+mod svn;
+mod worddiff;
+
+pub use crate::diffstat::{DiffStat, FileStat};
+pub use crate::email::{format_series, PatchEmail};
+pub use crate::filter::Glob;
+pub use crate::grep::LineMatch;
+pub use crate::inline::Granularity;
+pub use crate::interdiff::{range_diff, RangeDiffEntry};
+pub use crate::log::{parse_log, Commit};
+pub use crate::mapping::LineMapping;
+pub use crate::moves::{MoveOptions, WhitespaceMode};
+pub use crate::normalize::NormalizedPatch;
+pub use crate::patchid::PatchId;
+pub use crate::rebase::{Conflict, ConflictKind};
+pub use crate::review::Side;
+pub use crate::stats::ChangeStats;
+pub use crate::svn::{PropertyAction, PropertyChange, SvnRevision};
+pub use crate::worddiff::{WordDiffFormat, WordSegment};
 
 lazy_static! {
-    static ref RE_SOURCE_FILENAME: Regex = Regex::new(r"^--- (?P<filename>[^\t\n]+)(?:\t(?P<timestamp>[^\n]+))?").unwrap();
-    static ref RE_TARGET_FILENAME: Regex = Regex::new(r"^\+\+\+ (?P<filename>[^\t\n]+)(?:\t(?P<timestamp>[^\n]+))?").unwrap();
+    static ref RE_SOURCE_FILENAME: Regex = Regex::new(r#"^--- (?P<filename>"(?:[^"\\]|\\.)*"|[^\t\n]+)(?:\t(?P<timestamp>[^\n]+))?"#).unwrap();
+    static ref RE_TARGET_FILENAME: Regex = Regex::new(r#"^\+\+\+ (?P<filename>"(?:[^"\\]|\\.)*"|[^\t\n]+)(?:\t(?P<timestamp>[^\n]+))?"#).unwrap();
     static ref RE_HUNK_HEADER: Regex = Regex::new(r"^@@ -(?P<source_start>\d+)(?:,(?P<source_length>\d+))? \+(?P<target_start>\d+)(?:,(?P<target_length>\d+))? @@[ ]?(?P<section_header>.*)").unwrap();
+    static ref RE_FILE_HEADER_START: Regex = Regex::new(r"^(?:diff |Index: |=== )").unwrap();
+    static ref RE_GIT_DIFF_HEADER: Regex = Regex::new(r"^diff --git (?P<paths>.+)$").unwrap();
+    static ref RE_HG_DIFF_HEADER: Regex = Regex::new(r"^diff (?P<revisions>(?:-r \S+ )+)(?P<path>.+)$").unwrap();
+    static ref RE_SVN_INDEX_HEADER: Regex = Regex::new(r"^Index: (?P<path>.+)$").unwrap();
+    static ref RE_BZR_HEADER: Regex = Regex::new(r"^=== (?P<action>.+?) (?:file|directory|symlink) '(?P<path>[^']*)'(?: => '(?P<target>[^']*)')?").unwrap();
     static ref RE_HUNK_BODY_LINE: Regex = Regex::new(r"^(?P<line_type>[- \n\+\\]?)(?P<value>.*)").unwrap();
 }
 
+/// File name standing for a missing source or target
+const DEV_NULL: &str = "/dev/null";
+
 /// Diff line is added
-pub const LINE_TYPE_ADDED: &'static str = "+";
+pub const LINE_TYPE_ADDED: &str = "+";
 /// Diff line is removed
-pub const LINE_TYPE_REMOVED: &'static str = "-";
+pub const LINE_TYPE_REMOVED: &str = "-";
 /// Diff line is context
-pub const LINE_TYPE_CONTEXT: &'static str = " ";
+pub const LINE_TYPE_CONTEXT: &str = " ";
 /// Diff line is empty
-pub const LINE_TYPE_EMPTY: &'static str = "\n";
+pub const LINE_TYPE_EMPTY: &str = "\n";
 
 /// Error type
 #[derive(Debug, Clone)]
@@ -60,6 +110,8 @@ pub enum Error {
     UnexpectedHunk(String),
     /// Hunk line expected
     ExpectLine(String),
+    /// Source file does not match the patch
+    SourceMismatch(String),
 }
 
 impl fmt::Display for Error {
@@ -68,6 +120,7 @@ impl fmt::Display for Error {
             Error::TargetWithoutSource(ref l) => write!(f, "Target without source: {}", l),
             Error::UnexpectedHunk(ref l) => write!(f, "Unexpected hunk found: {}", l),
             Error::ExpectLine(ref l) => write!(f, "Hunk line expected: {}", l),
+            Error::SourceMismatch(ref l) => write!(f, "Source does not match patch: {}", l),
         }
     }
 }
@@ -78,6 +131,7 @@ impl error::Error for Error {
             Error::TargetWithoutSource(..) => "Target without source",
             Error::UnexpectedHunk(..) => "Unexpected hunk found",
             Error::ExpectLine(..) => "Hunk line expected",
+            Error::SourceMismatch(..) => "Source does not match patch",
         }
     }
 }
@@ -99,13 +153,16 @@ impl fmt::Display for LineType {
             LineType::Added => write!(f, "+"),
             LineType::Removed => write!(f, "-"),
             LineType::Context => write!(f, " "),
-            LineType::Empty => write!(f, "\n"),
+            LineType::Empty => write!(f, "\\"),
         }
     }
 }
 
 /// A diff line
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Lines compare and hash by their numbers, type and value only: the
+/// `segments` and `move_id` annotations are left out.
+#[derive(Debug, Clone)]
 pub struct Line {
     /// Source file line number
     pub source_line_no: Option<usize>,
@@ -117,6 +174,12 @@ pub struct Line {
     pub line_type: LineType,
     /// Diff line content value
     pub value: String,
+    /// Words of the line and how they changed, for lines parsed from a
+    /// word diff
+    pub segments: Vec<WordSegment>,
+    /// Block of moved lines this line belongs to, set by
+    /// `PatchSet::detect_moves`
+    pub move_id: Option<usize>,
 }
 
 impl Line {
@@ -127,6 +190,8 @@ impl Line {
             diff_line_no: 0usize,
             line_type,
             value: value.into(),
+            segments: vec![],
+            move_id: None,
         }
     }
 
@@ -152,6 +217,28 @@ impl fmt::Display for Line {
     }
 }
 
+impl PartialEq for Line {
+    fn eq(&self, other: &Line) -> bool {
+        self.source_line_no == other.source_line_no
+            && self.target_line_no == other.target_line_no
+            && self.diff_line_no == other.diff_line_no
+            && self.line_type == other.line_type
+            && self.value == other.value
+    }
+}
+
+impl Eq for Line {}
+
+impl Hash for Line {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source_line_no.hash(state);
+        self.target_line_no.hash(state);
+        self.diff_line_no.hash(state);
+        self.line_type.hash(state);
+        self.value.hash(state);
+    }
+}
+
 /// Each of the modified blocks of a file
 ///
 /// You can iterate over it to get ``Line``s.
@@ -194,6 +281,101 @@ impl Hunk {
         }
     }
 
+    /// Parse a hunk from its header and the lines following it, returning the
+    /// hunk and the count of lines that belong to it
+    fn parse(header_info: &Captures, diff: &[(usize, &str)]) -> Result<(Hunk, usize)> {
+        let source_start = header_info
+            .name("source_start")
+            .map_or("0", |s| s.as_str())
+            .parse::<usize>()
+            .unwrap();
+        let source_length = header_info
+            .name("source_length")
+            .map_or("1", |s| s.as_str())
+            .parse::<usize>()
+            .unwrap();
+        let target_start = header_info
+            .name("target_start")
+            .map_or("0", |s| s.as_str())
+            .parse::<usize>()
+            .unwrap();
+        let target_length = header_info
+            .name("target_length")
+            .map_or("1", |s| s.as_str())
+            .parse::<usize>()
+            .unwrap();
+        let section_header = header_info
+            .name("section_header")
+            .map_or("", |s| s.as_str());
+        let mut hunk = Hunk {
+            added: 0usize,
+            removed: 0usize,
+            lines: vec![],
+            source_start,
+            source_length,
+            target_start,
+            target_length,
+            section_header: section_header.to_owned(),
+        };
+        let mut source_line_no = source_start;
+        let mut target_line_no = target_start;
+        let expected_source_end = source_start + source_length;
+        let expected_target_end = target_start + target_length;
+        let mut consumed = 0;
+        for &(diff_line_no, line) in diff {
+            let complete =
+                source_line_no >= expected_source_end && target_line_no >= expected_target_end;
+            // a complete hunk may still be followed by a "\ No newline" marker
+            if complete && !line.starts_with('\\') {
+                break;
+            }
+            if let Some(valid_line) = RE_HUNK_BODY_LINE.captures(line) {
+                let line_type_str = valid_line.name("line_type").unwrap().as_str();
+                let line_type = match line_type_str {
+                    LINE_TYPE_ADDED => LineType::Added,
+                    LINE_TYPE_REMOVED => LineType::Removed,
+                    LINE_TYPE_CONTEXT => LineType::Context,
+                    LINE_TYPE_EMPTY => LineType::Context,
+                    "" => LineType::Context,
+                    _ if line.starts_with("\\ No newline at end of") => LineType::Empty,
+                    _ => return Err(Error::ExpectLine(line.to_owned())),
+                };
+                let value = valid_line.name("value").unwrap().as_str();
+                let mut original_line = Line {
+                    source_line_no: None,
+                    target_line_no: None,
+                    diff_line_no: diff_line_no + 1,
+                    line_type: line_type.clone(),
+                    value: value.to_owned(),
+                    segments: vec![],
+                    move_id: None,
+                };
+                match line_type {
+                    LineType::Added => {
+                        original_line.target_line_no = Some(target_line_no);
+                        target_line_no += 1;
+                    }
+                    LineType::Removed => {
+                        original_line.source_line_no = Some(source_line_no);
+                        source_line_no += 1;
+                    }
+                    LineType::Context => {
+                        original_line.target_line_no = Some(target_line_no);
+                        target_line_no += 1;
+                        original_line.source_line_no = Some(source_line_no);
+                        source_line_no += 1;
+                    }
+                    _ => {}
+                }
+                hunk.append(original_line);
+                consumed += 1;
+            } else {
+                return Err(Error::ExpectLine(line.to_owned()));
+            }
+        }
+        Ok((hunk, consumed))
+    }
+
     /// Count of lines added
     pub fn added(&self) -> usize {
         self.added
@@ -208,8 +390,8 @@ impl Hunk {
     pub fn source_lines(&self) -> Vec<Line> {
         self.lines
             .iter()
-            .cloned()
             .filter(|l| l.is_context() || l.is_removed())
+            .cloned()
             .collect()
     }
 
@@ -217,17 +399,17 @@ impl Hunk {
     pub fn target_lines(&self) -> Vec<Line> {
         self.lines
             .iter()
-            .cloned()
             .filter(|l| l.is_context() || l.is_added())
+            .cloned()
             .collect()
     }
 
     /// Append new line into hunk
     pub fn append(&mut self, line: Line) {
         if line.is_added() {
-            self.added = self.added + 1;
+            self.added += 1;
         } else if line.is_removed() {
-            self.removed = self.removed + 1;
+            self.removed += 1;
         } else if line.is_context() {
         }
         self.lines.push(line);
@@ -296,31 +478,187 @@ impl IndexMut<usize> for Hunk {
     }
 }
 
+/// VCS specific line introducing a file in a diff
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VcsHeader {
+    /// `diff --git a/path b/path`
+    Git { source: String, target: String },
+    /// `diff -r <rev> [-r <rev>] path` from Mercurial
+    Hg {
+        revisions: Vec<String>,
+        path: String,
+    },
+    /// `Index: path` from Subversion
+    Svn { path: String },
+    /// `=== <action> file 'path'` from Bazaar, with the new path for renames
+    Bzr {
+        action: String,
+        path: String,
+        target: Option<String>,
+    },
+}
+
+impl VcsHeader {
+    /// Parse a single header line
+    pub fn parse(line: &str) -> Option<VcsHeader> {
+        if let Some(captures) = RE_GIT_DIFF_HEADER.captures(line) {
+            let (source, target) = split_git_paths(&captures["paths"])?;
+            return Some(VcsHeader::Git { source, target });
+        }
+        if let Some(captures) = RE_HG_DIFF_HEADER.captures(line) {
+            let revisions = captures["revisions"]
+                .split_whitespace()
+                .filter(|r| *r != "-r")
+                .map(str::to_owned)
+                .collect();
+            return Some(VcsHeader::Hg {
+                revisions,
+                path: captures["path"].to_owned(),
+            });
+        }
+        if let Some(captures) = RE_SVN_INDEX_HEADER.captures(line) {
+            return Some(VcsHeader::Svn {
+                path: captures["path"].to_owned(),
+            });
+        }
+        if let Some(captures) = RE_BZR_HEADER.captures(line) {
+            return Some(VcsHeader::Bzr {
+                action: captures["action"].to_owned(),
+                path: captures["path"].to_owned(),
+                target: captures.name("target").map(|t| t.as_str().to_owned()),
+            });
+        }
+        None
+    }
+}
+
+/// Split the paths of a `diff --git` line, preferring the symmetric split
+/// so that names containing spaces survive
+fn split_git_paths(paths: &str) -> Option<(String, String)> {
+    if let Some((source, target)) = quote::split_name(paths) {
+        return Some((quote::unquote(source), quote::unquote(target.trim_start())));
+    }
+    let mid = paths.len() / 2;
+    if paths.len() % 2 == 1 && paths.is_char_boundary(mid) && paths[mid..].starts_with(' ') {
+        let (source, target) = (&paths[..mid], &paths[mid + 1..]);
+        if source.get(2..) == target.get(2..) {
+            return Some((source.to_owned(), target.to_owned()));
+        }
+    }
+    let (source, target) = match paths.find(" b/").or_else(|| paths.find(" \"")) {
+        Some(pos) => (&paths[..pos], &paths[pos + 1..]),
+        None => paths.split_at(paths.find(' ')?),
+    };
+    Some((source.to_owned(), quote::unquote(target.trim_start())))
+}
+
+/// How to turn the file names of `---` and `+++` lines into paths
+///
+/// By default the `a/` and `b/` prefixes git adds are removed.
+///
+/// ```
+/// use unidiff::{PatchedFile, PathStyle};
+///
+/// let file = PatchedFile::new("old/src/lib.rs", "new/src/lib.rs");
+/// assert_eq!("src/lib.rs", file.path_with(&PathStyle::with_prefixes("old/", "new/")));
+/// assert_eq!("lib.rs", file.path_with(&PathStyle::strip(2)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathStyle {
+    /// Count of leading path components to remove, like `patch -pN`;
+    /// the prefixes are ignored when set
+    pub strip: Option<usize>,
+    /// Prefix of source file names
+    pub source_prefix: String,
+    /// Prefix of target file names
+    pub target_prefix: String,
+}
+
+impl PathStyle {
+    /// Remove `count` leading path components, like `patch -pN`
+    pub fn strip(count: usize) -> PathStyle {
+        PathStyle {
+            strip: Some(count),
+            ..PathStyle::default()
+        }
+    }
+
+    /// Remove custom prefixes, e.g. from `git diff --src-prefix=old/ --dst-prefix=new/`
+    pub fn with_prefixes<T: Into<String>>(source_prefix: T, target_prefix: T) -> PathStyle {
+        PathStyle {
+            strip: None,
+            source_prefix: source_prefix.into(),
+            target_prefix: target_prefix.into(),
+        }
+    }
+
+    /// Keep names as they are, e.g. for `git diff --no-prefix`
+    pub fn no_prefix() -> PathStyle {
+        PathStyle::with_prefixes("", "")
+    }
+}
+
+impl Default for PathStyle {
+    fn default() -> PathStyle {
+        PathStyle::with_prefixes("a/", "b/")
+    }
+}
+
+/// Remove `count` leading components from `name`, treating repeated
+/// slashes as one
+fn strip_components(name: &str, count: usize) -> String {
+    let mut rest = name;
+    for _ in 0..count {
+        match rest.find('/') {
+            Some(pos) => rest = rest[pos + 1..].trim_start_matches('/'),
+            None => break,
+        }
+    }
+    rest.to_owned()
+}
+
 /// Patch updated file, contains a list of Hunks
 ///
 /// You can iterate over it to get ``Hunk``s.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PatchedFile {
+    /// Raw lines preceding the `---` line, e.g. `diff --git` and `index` lines
+    pub header: Vec<String>,
     /// Source file name
     pub source_file: String,
     /// Source file timestamp
     pub source_timestamp: Option<String>,
+    /// Source revision svn writes in place of a timestamp
+    pub source_revision: Option<SvnRevision>,
     /// Target file name
     pub target_file: String,
     /// Target file timestamp
     pub target_timestamp: Option<String>,
+    /// Target revision svn writes in place of a timestamp
+    pub target_revision: Option<SvnRevision>,
+    /// Subversion property changes
+    pub properties: Vec<PropertyChange>,
     hunks: Vec<Hunk>,
+    /// Were the source and target names quoted where they were parsed from
+    source_quoted: bool,
+    target_quoted: bool,
 }
 
 impl PatchedFile {
     /// Initialize a new PatchedFile instance
     pub fn new<T: Into<String>>(source_file: T, target_file: T) -> PatchedFile {
         PatchedFile {
+            header: vec![],
             source_file: source_file.into(),
             target_file: target_file.into(),
             source_timestamp: None,
+            source_revision: None,
             target_timestamp: None,
+            target_revision: None,
+            properties: vec![],
             hunks: vec![],
+            source_quoted: false,
+            target_quoted: false,
         }
     }
 
@@ -331,48 +669,100 @@ impl PatchedFile {
         hunks: Vec<Hunk>,
     ) -> PatchedFile {
         PatchedFile {
+            header: vec![],
             source_file: source_file.into(),
             target_file: target_file.into(),
             source_timestamp: None,
+            source_revision: None,
             target_timestamp: None,
-            hunks: hunks,
+            target_revision: None,
+            properties: vec![],
+            hunks,
+            source_quoted: false,
+            target_quoted: false,
         }
     }
 
     /// Patched file relative path
+    ///
+    /// This is the source path, or the target path for added files.
     pub fn path(&self) -> String {
-        if self.source_file.starts_with("a/") && self.target_file.starts_with("b/") {
-            return self.source_file[2..].to_owned();
+        self.path_with(&PathStyle::default())
+    }
+
+    /// Patched file path resolved with `style`
+    pub fn path_with(&self, style: &PathStyle) -> String {
+        self.source_path_with(style)
+            .or_else(|| self.target_path_with(style))
+            .unwrap_or_else(|| self.source_file.clone())
+    }
+
+    /// Source file path without its `a/` prefix, `None` for `/dev/null`
+    pub fn source_path(&self) -> Option<String> {
+        self.source_path_with(&PathStyle::default())
+    }
+
+    /// Target file path without its `b/` prefix, `None` for `/dev/null`
+    pub fn target_path(&self) -> Option<String> {
+        self.target_path_with(&PathStyle::default())
+    }
+
+    /// Source file path resolved with `style`, `None` for `/dev/null`
+    pub fn source_path_with(&self, style: &PathStyle) -> Option<String> {
+        self.resolve_path(&self.source_file, &style.source_prefix, style)
+    }
+
+    /// Target file path resolved with `style`, `None` for `/dev/null`
+    pub fn target_path_with(&self, style: &PathStyle) -> Option<String> {
+        self.resolve_path(&self.target_file, &style.target_prefix, style)
+    }
+
+    fn resolve_path(&self, name: &str, prefix: &str, style: &PathStyle) -> Option<String> {
+        if name == DEV_NULL {
+            return None;
         }
-        if self.source_file.starts_with("a/") && "/dev/null" == &self.target_file {
-            return self.source_file[2..].to_owned();
+        if let Some(strip) = style.strip {
+            return Some(strip_components(name, strip));
         }
-        if self.target_file.starts_with("b/") && "/dev/null" == &self.source_file {
-            return self.target_file[2..].to_owned();
+        // only strip prefixes when both names carry them, so that a real
+        // top level directory called `a` survives in diffs without prefixes
+        let has_prefix = |name: &str, prefix: &str| name == DEV_NULL || name.starts_with(prefix);
+        if has_prefix(&self.source_file, &style.source_prefix)
+            && has_prefix(&self.target_file, &style.target_prefix)
+        {
+            return Some(name[prefix.len()..].to_owned());
         }
-        self.source_file.clone()
+        Some(name.to_owned())
+    }
+
+    /// VCS specific line of the header block, if any
+    pub fn vcs_header(&self) -> Option<VcsHeader> {
+        self.header.iter().rev().find_map(|l| VcsHeader::parse(l))
     }
 
     /// Count of lines added
     pub fn added(&self) -> usize {
-        self.hunks.iter().map(|h| h.added).fold(0, |acc, x| acc + x)
+        self.hunks.iter().map(|h| h.added).sum()
     }
 
     /// Count of lines removed
     pub fn removed(&self) -> usize {
-        self.hunks
-            .iter()
-            .map(|h| h.removed)
-            .fold(0, |acc, x| acc + x)
+        self.hunks.iter().map(|h| h.removed).sum()
     }
 
     /// Is this file newly added
     pub fn is_added_file(&self) -> bool {
+        if self.hunks.is_empty() {
+            return self.source_file == DEV_NULL;
+        }
         self.hunks.len() == 1 && self.hunks[0].source_start == 0 && self.hunks[0].source_length == 0
     }
 
     /// Is this file removed
     pub fn is_removed_file(&self) -> bool {
+        if self.hunks.is_empty() {
+            return self.target_file == DEV_NULL;
+        }
         self.hunks.len() == 1 && self.hunks[0].target_start == 0 && self.hunks[0].target_length == 0
     }
 
@@ -381,93 +771,30 @@ impl PatchedFile {
         !self.is_added_file() && !self.is_removed_file()
     }
 
-    fn parse_hunk(&mut self, header: &str, diff: &[(usize, &str)]) -> Result<()> {
+    /// Is this a binary file, whose changes git does not show as lines
+    pub fn is_binary(&self) -> bool {
+        self.header
+            .iter()
+            .any(|l| l.starts_with("Binary files ") || l == "GIT binary patch")
+    }
+
+    /// Is this a git file without `---` and `+++` lines
+    fn is_header_only(&self) -> bool {
+        self.hunks.is_empty()
+            && self.properties.is_empty()
+            && self
+                .header
+                .first()
+                .is_some_and(|l| RE_GIT_DIFF_HEADER.is_match(l))
+    }
+
+    /// Parse a hunk from the lines following its header, returning the count
+    /// of lines that belong to it
+    fn parse_hunk(&mut self, header: &str, diff: &[(usize, &str)]) -> Result<usize> {
         let header_info = RE_HUNK_HEADER.captures(header).unwrap();
-        let source_start = header_info
-            .name("source_start")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let source_length = header_info
-            .name("source_length")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let target_start = header_info
-            .name("target_start")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let target_length = header_info
-            .name("target_length")
-            .map_or("0", |s| s.as_str())
-            .parse::<usize>()
-            .unwrap();
-        let section_header = header_info
-            .name("section_header")
-            .map_or("", |s| s.as_str());
-        let mut hunk = Hunk {
-            added: 0usize,
-            removed: 0usize,
-            lines: vec![],
-            source_start,
-            source_length,
-            target_start,
-            target_length,
-            section_header: section_header.to_owned(),
-        };
-        let mut source_line_no = source_start;
-        let mut target_line_no = target_start;
-        let expected_source_end = source_start + source_length;
-        let expected_target_end = target_start + target_length;
-        for &(diff_line_no, line) in diff {
-            if let Some(valid_line) = RE_HUNK_BODY_LINE.captures(line) {
-                let line_type_str = valid_line.name("line_type").unwrap().as_str();
-                let line_type = match line_type_str {
-                    LINE_TYPE_ADDED => LineType::Added,
-                    LINE_TYPE_REMOVED => LineType::Removed,
-                    LINE_TYPE_CONTEXT => LineType::Context,
-                    LINE_TYPE_EMPTY => LineType::Context,
-                    "" => LineType::Context,
-                    _ if line.ends_with("\\ No newline at end of file") => LineType::Empty,
-                    _ => return Err(Error::ExpectLine(line.to_owned())),
-                };
-                let value = valid_line.name("value").unwrap().as_str();
-                let mut original_line = Line {
-                    source_line_no: None,
-                    target_line_no: None,
-                    diff_line_no: diff_line_no + 1,
-                    line_type: line_type.clone(),
-                    value: value.to_owned(),
-                };
-                match line_type {
-                    LineType::Added => {
-                        original_line.target_line_no = Some(target_line_no);
-                        target_line_no = target_line_no + 1;
-                    }
-                    LineType::Removed => {
-                        original_line.source_line_no = Some(source_line_no);
-                        source_line_no = source_line_no + 1;
-                    }
-                    LineType::Context => {
-                        original_line.target_line_no = Some(target_line_no);
-                        target_line_no = target_line_no + 1;
-                        original_line.source_line_no = Some(source_line_no);
-                        source_line_no = source_line_no + 1;
-                    }
-                    _ => {}
-                }
-                hunk.append(original_line);
-                if source_line_no >= expected_source_end && target_line_no >= expected_target_end {
-                    // FIXME: sync with upstream version
-                    break;
-                }
-            } else {
-                return Err(Error::ExpectLine(line.to_owned()));
-            }
-        }
+        let (hunk, consumed) = Hunk::parse(&header_info, diff)?;
         self.hunks.push(hunk);
-        Ok(())
+        Ok(consumed)
     }
 
     /// Count of hunks
@@ -491,15 +818,43 @@ impl PatchedFile {
 
 impl fmt::Display for PatchedFile {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let source = format!("--- {}\n", self.source_file);
-        let target = format!("+++ {}\n", self.target_file);
+        if !self.is_header_only() {
+            for line in &self.header {
+                writeln!(f, "{}", line)?;
+            }
+        }
+        // svn writes no file names for changes to properties only
+        let properties_only = self.hunks.is_empty()
+            && !self.properties.is_empty()
+            && self.source_revision.is_none()
+            && self.target_revision.is_none();
+        if properties_only {
+            return svn::fmt_properties(f, &self.path(), &self.properties);
+        }
+        // nor does git for binary files, renames and mode changes
+        if self.is_header_only() {
+            return write!(f, "{}", self.header.join("\n"));
+        }
+        let source = format!(
+            "--- {}\n",
+            quote::requote(&self.source_file, self.source_quoted)
+        );
+        let target = format!(
+            "+++ {}\n",
+            quote::requote(&self.target_file, self.target_quoted)
+        );
         let hunks = self
             .hunks
             .iter()
             .map(|h| h.to_string())
             .collect::<Vec<String>>()
             .join("\n");
-        write!(f, "{}{}{}", source, target, hunks)
+        write!(f, "{}{}{}", source, target, hunks)?;
+        if !self.properties.is_empty() {
+            write!(f, "\n\n")?;
+            svn::fmt_properties(f, &self.path(), &self.properties)?;
+        }
+        Ok(())
     }
 }
 
@@ -546,6 +901,8 @@ impl IndexMut<usize> for PatchedFile {
 #[derive(Clone)]
 pub struct PatchSet {
     files: Vec<PatchedFile>,
+    preamble: Vec<String>,
+    epilogue: Vec<String>,
     #[cfg(feature = "encoding")]
     encoding: &'static encoding_rs::Encoding,
 }
@@ -554,6 +911,8 @@ impl fmt::Debug for PatchSet {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("PatchSet")
             .field("files", &self.files)
+            .field("preamble", &self.preamble)
+            .field("epilogue", &self.epilogue)
             .finish()
     }
 }
@@ -569,8 +928,8 @@ impl PatchSet {
     pub fn added_files(&self) -> Vec<PatchedFile> {
         self.files
             .iter()
-            .cloned()
             .filter(|f| f.is_added_file())
+            .cloned()
             .collect()
     }
 
@@ -578,8 +937,8 @@ impl PatchSet {
     pub fn removed_files(&self) -> Vec<PatchedFile> {
         self.files
             .iter()
-            .cloned()
             .filter(|f| f.is_removed_file())
+            .cloned()
             .collect()
     }
 
@@ -587,8 +946,8 @@ impl PatchSet {
     pub fn modified_files(&self) -> Vec<PatchedFile> {
         self.files
             .iter()
-            .cloned()
             .filter(|f| f.is_modified_file())
+            .cloned()
             .collect()
     }
 
@@ -596,6 +955,8 @@ impl PatchSet {
     pub fn new() -> PatchSet {
         PatchSet {
             files: vec![],
+            preamble: vec![],
+            epilogue: vec![],
             #[cfg(feature = "encoding")]
             encoding: encoding_rs::UTF_8,
         }
@@ -606,6 +967,8 @@ impl PatchSet {
     pub fn with_encoding(coding: &'static encoding_rs::Encoding) -> PatchSet {
         PatchSet {
             files: vec![],
+            preamble: vec![],
+            epilogue: vec![],
             encoding: coding,
         }
     }
@@ -616,6 +979,8 @@ impl PatchSet {
         let codec = encoding_rs::Encoding::for_label(coding.as_ref().as_bytes());
         PatchSet {
             files: vec![],
+            preamble: vec![],
+            epilogue: vec![],
             encoding: codec.unwrap_or(encoding_rs::UTF_8),
         }
     }
@@ -630,26 +995,60 @@ impl PatchSet {
     /// Parse diff from string
     pub fn parse<T: AsRef<str>>(&mut self, input: T) -> Result<()> {
         let input = input.as_ref();
-        let mut current_file: Option<PatchedFile> = None;
         let diff: Vec<(usize, &str)> = input.lines().enumerate().collect();
+        self.parse_lines(&diff)
+    }
+
+    /// Parse diff from lines paired with their zero-based line number
+    pub(crate) fn parse_lines(&mut self, diff: &[(usize, &str)]) -> Result<()> {
+        let mut current_file: Option<PatchedFile> = None;
         let mut source_file: Option<String> = None;
         let mut source_timestamp: Option<String> = None;
-
-        for &(line_no, line) in &diff {
-            // check for source file header
-            if let Some(captures) = RE_SOURCE_FILENAME.captures(line) {
+        let mut source_revision: Option<SvnRevision> = None;
+        let mut source_quoted = false;
+        let mut header: Vec<String> = vec![];
+        // start of the text that belongs to no hunk
+        let mut gap_start = 0;
+
+        let mut idx = 0;
+        while idx < diff.len() {
+            let line = diff[idx].1;
+            // a git file without hunks ends where the next one starts
+            if RE_GIT_DIFF_HEADER.is_match(line)
+                && self.push_header_only_file(&mut current_file, &diff[gap_start..idx])
+            {
+                gap_start = idx;
+            }
+            // check for source file header, which must be followed by the target
+            let is_source_header = diff
+                .get(idx + 1)
+                .is_some_and(|&(_, next)| RE_TARGET_FILENAME.is_match(next));
+            if let Some(captures) = RE_SOURCE_FILENAME
+                .captures(line)
+                .filter(|_| is_source_header)
+            {
                 source_file = match captures.name("filename") {
-                    Some(ref filename) => Some(filename.as_str().to_owned()),
+                    Some(ref filename) => Some(quote::unquote(filename.as_str())),
                     None => Some("".to_owned()),
                 };
+                source_quoted = captures
+                    .name("filename")
+                    .is_some_and(|f| f.as_str().starts_with('"'));
                 source_timestamp = match captures.name("timestamp") {
                     Some(ref timestamp) => Some(timestamp.as_str().to_owned()),
                     None => Some("".to_owned()),
                 };
+                source_revision = source_timestamp.as_deref().and_then(SvnRevision::parse);
+                if source_revision.is_some() {
+                    source_timestamp = None;
+                }
                 if let Some(patched_file) = current_file {
-                    self.files.push(patched_file.clone());
+                    self.files.push(patched_file);
                     current_file = None;
                 }
+                header = self.take_header(&diff[gap_start..idx]);
+                idx += 1;
+                gap_start = idx;
                 continue;
             }
             // check for target file header
@@ -657,40 +1056,143 @@ impl PatchSet {
                 if current_file.is_some() {
                     return Err(Error::TargetWithoutSource(line.to_owned()));
                 }
+                let source_file = match source_file.take() {
+                    Some(source_file) => source_file,
+                    None => return Err(Error::TargetWithoutSource(line.to_owned())),
+                };
                 let target_file = match captures.name("filename") {
-                    Some(ref filename) => Some(filename.as_str().to_owned()),
+                    Some(ref filename) => Some(quote::unquote(filename.as_str())),
                     None => Some("".to_owned()),
                 };
-                let target_timestamp = match captures.name("timestamp") {
+                let target_quoted = captures
+                    .name("filename")
+                    .is_some_and(|f| f.as_str().starts_with('"'));
+                let mut target_timestamp = match captures.name("timestamp") {
                     Some(ref timestamp) => Some(timestamp.as_str().to_owned()),
                     None => Some("".to_owned()),
                 };
+                let target_revision = target_timestamp.as_deref().and_then(SvnRevision::parse);
+                if target_revision.is_some() {
+                    target_timestamp = None;
+                }
 
                 // add current file to PatchSet
                 current_file = Some(PatchedFile {
-                    source_file: source_file.clone().unwrap(),
+                    header: std::mem::take(&mut header),
+                    source_file,
                     target_file: target_file.clone().unwrap(),
                     source_timestamp: source_timestamp.clone(),
+                    source_revision,
                     target_timestamp: target_timestamp.clone(),
+                    target_revision,
+                    properties: vec![],
                     hunks: Vec::new(),
+                    source_quoted,
+                    target_quoted,
                 });
+                idx += 1;
+                gap_start = idx;
+                continue;
+            }
+            // check for svn property changes, which follow the file's hunks
+            if let Some(captures) = svn::RE_PROPERTY_CHANGES.captures(line) {
+                let path = &captures["path"];
+                let same_file = current_file
+                    .as_ref()
+                    .is_some_and(|f| f.source_file == path || f.target_file == path);
+                if !same_file {
+                    // property only change, without file names
+                    if let Some(patched_file) = current_file.take() {
+                        self.files.push(patched_file);
+                    }
+                    let mut patched_file = PatchedFile::new(path, path);
+                    patched_file.header = self.take_header(&diff[gap_start..idx]);
+                    current_file = Some(patched_file);
+                }
+                let (properties, consumed) = svn::parse_properties(&diff[idx + 1..])?;
+                if let Some(ref mut patched_file) = current_file {
+                    patched_file.properties.extend(properties);
+                }
+                idx += 1 + consumed;
+                gap_start = idx;
                 continue;
             }
             // check for hunk header
             if RE_HUNK_HEADER.is_match(line) {
                 if let Some(ref mut patched_file) = current_file {
-                    patched_file.parse_hunk(line, &diff[line_no + 1..])?;
+                    idx += 1 + patched_file.parse_hunk(line, &diff[idx + 1..])?;
+                    gap_start = idx;
+                    continue;
                 } else {
                     return Err(Error::UnexpectedHunk(line.to_owned()));
                 }
             }
+            idx += 1;
+        }
+        if self.push_header_only_file(&mut current_file, &diff[gap_start..]) {
+            gap_start = diff.len();
         }
         if let Some(patched_file) = current_file {
-            self.files.push(patched_file.clone());
+            self.files.push(patched_file);
+        }
+        let rest = diff[gap_start..].iter().map(|&(_, l)| l.to_owned());
+        if self.files.is_empty() {
+            self.preamble.extend(rest);
+        } else {
+            self.epilogue.extend(rest);
         }
         Ok(())
     }
 
+    /// Add the git file without `---` and `+++` lines, such as a binary file,
+    /// a rename or a mode change, whose header ends `gap`, if there is one
+    fn push_header_only_file(
+        &mut self,
+        current_file: &mut Option<PatchedFile>,
+        gap: &[(usize, &str)],
+    ) -> bool {
+        let start = match gap
+            .iter()
+            .rposition(|&(_, l)| RE_GIT_DIFF_HEADER.is_match(l))
+        {
+            Some(start) => start,
+            None => return false,
+        };
+        let (source, target) = match VcsHeader::parse(gap[start].1) {
+            Some(VcsHeader::Git { source, target }) => (source, target),
+            _ => return false,
+        };
+        if let Some(patched_file) = current_file.take() {
+            self.files.push(patched_file);
+        }
+        let mut patched_file = PatchedFile::new(source, target);
+        patched_file.header = self.take_header(gap);
+        for line in &patched_file.header {
+            if line.starts_with("new file mode ") {
+                patched_file.source_file = DEV_NULL.to_owned();
+            } else if line.starts_with("deleted file mode ") {
+                patched_file.target_file = DEV_NULL.to_owned();
+            }
+        }
+        self.files.push(patched_file);
+        true
+    }
+
+    /// Split the text preceding a `---` line into the file header block and,
+    /// for the first file, the patch set preamble
+    fn take_header(&mut self, gap: &[(usize, &str)]) -> Vec<String> {
+        let mut split = 0;
+        if self.files.is_empty() {
+            split = gap
+                .iter()
+                .rposition(|&(_, l)| RE_FILE_HEADER_START.is_match(l))
+                .unwrap_or(gap.len());
+            self.preamble
+                .extend(gap[..split].iter().map(|&(_, l)| l.to_owned()));
+        }
+        gap[split..].iter().map(|&(_, l)| l.to_owned()).collect()
+    }
+
     /// Count of patched files
     pub fn len(&self) -> usize {
         self.files.len()
@@ -705,6 +1207,27 @@ impl PatchSet {
         &self.files
     }
 
+    /// A patch set with the same metadata as this one and other files
+    pub(crate) fn with_files(&self, files: Vec<PatchedFile>) -> PatchSet {
+        PatchSet {
+            files,
+            preamble: self.preamble.clone(),
+            epilogue: self.epilogue.clone(),
+            #[cfg(feature = "encoding")]
+            encoding: self.encoding,
+        }
+    }
+
+    /// Text before the first file, e.g. a commit message
+    pub fn preamble(&self) -> &[String] {
+        &self.preamble
+    }
+
+    /// Text after the last hunk of the last file
+    pub fn epilogue(&self) -> &[String] {
+        &self.epilogue
+    }
+
     pub fn files_mut(&mut self) -> &mut [PatchedFile] {
         &mut self.files
     }
@@ -712,13 +1235,20 @@ impl PatchSet {
 
 impl fmt::Display for PatchSet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.preamble {
+            writeln!(f, "{}", line)?;
+        }
         let diff = self
             .files
             .iter()
             .map(|f| f.to_string())
             .collect::<Vec<String>>()
             .join("\n");
-        write!(f, "{}", diff)
+        write!(f, "{}", diff)?;
+        for line in &self.epilogue {
+            write!(f, "\n{}", line)?;
+        }
+        Ok(())
     }
 }
 
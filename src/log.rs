@@ -0,0 +1,167 @@
+//! `git log -p` and `hg log -p` parsing
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{PatchSet, Result};
+
+lazy_static! {
+    static ref RE_GIT_COMMIT: Regex = Regex::new(r"^commit (?P<id>[0-9a-fA-F]{7,64})\b").unwrap();
+    static ref RE_HG_CHANGESET: Regex = Regex::new(r"^changeset:\s+(?P<id>\S+)").unwrap();
+    static ref RE_HEADER: Regex =
+        Regex::new(r"^(?P<key>[A-Za-z][A-Za-z-]*):\s*(?P<value>.*)$").unwrap();
+}
+
+/// A commit from a `log -p` stream, with its own patch set
+#[derive(Debug, Clone)]
+pub struct Commit {
+    /// Commit id, e.g. the git sha or the hg `rev:node`
+    pub id: String,
+    /// Parent commit ids, when listed in the log
+    pub parents: Vec<String>,
+    /// Commit author
+    pub author: Option<String>,
+    /// Commit date, as printed in the log
+    pub date: Option<String>,
+    /// Commit message, without indentation
+    pub message: String,
+    /// All header lines in order, e.g. `("Author", "A U Thor <a@example.com>")`
+    pub headers: Vec<(String, String)>,
+    /// Changes introduced by this commit
+    pub patch: PatchSet,
+}
+
+impl Commit {
+    fn new<T: Into<String>>(id: T) -> Commit {
+        Commit {
+            id: id.into(),
+            parents: vec![],
+            author: None,
+            date: None,
+            message: String::new(),
+            headers: vec![],
+            patch: PatchSet::new(),
+        }
+    }
+
+    /// First line of the commit message
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or("")
+    }
+
+    /// Value of the first header named `key`, compared case-insensitively
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Parse the output of `git log -p` or `hg log -p` into commits
+///
+/// Diff line numbers are counted from the start of the whole log.
+pub fn parse_log<T: AsRef<str>>(input: T) -> Result<Vec<Commit>> {
+    let lines: Vec<(usize, &str)> = input.as_ref().lines().enumerate().collect();
+    let starts: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, &(_, line))| RE_GIT_COMMIT.is_match(line) || RE_HG_CHANGESET.is_match(line))
+        .map(|(idx, _)| idx)
+        .collect();
+    let mut commits = Vec::with_capacity(starts.len());
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).cloned().unwrap_or(lines.len());
+        let chunk = &lines[start..end];
+        let commit = if let Some(captures) = RE_GIT_COMMIT.captures(chunk[0].1) {
+            parse_git_commit(&captures["id"], chunk)?
+        } else {
+            let captures = RE_HG_CHANGESET.captures(chunk[0].1).unwrap();
+            parse_hg_changeset(&captures["id"], chunk)?
+        };
+        commits.push(commit);
+    }
+    Ok(commits)
+}
+
+fn parse_git_commit(id: &str, chunk: &[(usize, &str)]) -> Result<Commit> {
+    let mut commit = Commit::new(id);
+    let mut idx = 1;
+    while idx < chunk.len() {
+        let line = chunk[idx].1;
+        let captures = match RE_HEADER.captures(line) {
+            Some(captures) => captures,
+            None => break,
+        };
+        let key = &captures["key"];
+        let value = captures.name("value").map_or("", |v| v.as_str().trim());
+        match key {
+            "Author" => commit.author = Some(value.to_owned()),
+            "Date" | "AuthorDate" => commit.date = Some(value.to_owned()),
+            "Merge" => commit.parents = value.split_whitespace().map(str::to_owned).collect(),
+            _ => {}
+        }
+        commit.headers.push((key.to_owned(), value.to_owned()));
+        idx += 1;
+    }
+    // The message is indented by four spaces and runs until the diff starts
+    let mut message = vec![];
+    while idx < chunk.len() {
+        let line = chunk[idx].1;
+        if line.is_empty() {
+            message.push("");
+        } else if let Some(text) = line.strip_prefix("    ") {
+            message.push(text);
+        } else {
+            break;
+        }
+        idx += 1;
+    }
+    commit.message = join_message(&message);
+    commit.patch.parse_lines(&chunk[idx..])?;
+    Ok(commit)
+}
+
+fn parse_hg_changeset(id: &str, chunk: &[(usize, &str)]) -> Result<Commit> {
+    let mut commit = Commit::new(id);
+    let mut idx = 1;
+    let mut message = vec![];
+    while idx < chunk.len() {
+        let line = chunk[idx].1;
+        if line == "description:" {
+            idx += 1;
+            while idx < chunk.len() && !is_diff_start(chunk[idx].1) {
+                message.push(chunk[idx].1);
+                idx += 1;
+            }
+            break;
+        }
+        let captures = match RE_HEADER.captures(line) {
+            Some(captures) => captures,
+            None => break,
+        };
+        let key = &captures["key"];
+        let value = captures.name("value").map_or("", |v| v.as_str().trim());
+        match key {
+            "user" => commit.author = Some(value.to_owned()),
+            "date" => commit.date = Some(value.to_owned()),
+            "parent" => commit.parents.push(value.to_owned()),
+            "summary" => message = vec![value],
+            _ => {}
+        }
+        commit.headers.push((key.to_owned(), value.to_owned()));
+        idx += 1;
+    }
+    commit.message = join_message(&message);
+    commit.patch.parse_lines(&chunk[idx..])?;
+    Ok(commit)
+}
+
+fn is_diff_start(line: &str) -> bool {
+    line.starts_with("diff ") || line.starts_with("--- ") || line.starts_with("Index: ")
+}
+
+fn join_message(lines: &[&str]) -> String {
+    let message = lines.join("\n");
+    message.trim_matches('\n').to_owned()
+}
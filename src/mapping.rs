@@ -0,0 +1,87 @@
+//! Mapping line numbers between the source and the target of a file
+
+use crate::{LineType, PatchedFile};
+
+/// Where a line of one side of a patched file is on the other side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineMapping {
+    /// The line is on both sides, with this number on the other one
+    Unchanged(usize),
+    /// The line was removed; the number is the line on the target side
+    /// that follows the removal
+    Removed(usize),
+    /// The line was added; the number is the line on the source side that
+    /// follows the addition
+    Added(usize),
+}
+
+impl LineMapping {
+    /// The line number on the other side, if the line is there
+    pub fn line(&self) -> Option<usize> {
+        match *self {
+            LineMapping::Unchanged(line) => Some(line),
+            _ => None,
+        }
+    }
+}
+
+impl PatchedFile {
+    /// Map `line` from one side to the other, `from_source` telling which
+    fn map_line(&self, line: usize, from_source: bool) -> LineMapping {
+        let pick = |(source, target): (usize, usize)| {
+            if from_source {
+                (source, target)
+            } else {
+                (target, source)
+            }
+        };
+        let mut offset = 0isize;
+        for hunk in &self.hunks {
+            let (start, _) = pick(hunk.start_positions());
+            let (length, other_length) = pick((hunk.source_length, hunk.target_length));
+            if line < start {
+                break;
+            }
+            if line < start + length {
+                let positions = hunk.line_positions();
+                for (l, &position) in hunk.lines.iter().zip(&positions) {
+                    let (this, other) = pick(position);
+                    if this != line {
+                        continue;
+                    }
+                    match (&l.line_type, from_source) {
+                        (LineType::Context, _) => return LineMapping::Unchanged(other),
+                        (LineType::Removed, true) => return LineMapping::Removed(other),
+                        (LineType::Added, false) => return LineMapping::Added(other),
+                        _ => {}
+                    }
+                }
+            }
+            offset += other_length as isize - length as isize;
+        }
+        LineMapping::Unchanged((line as isize + offset) as usize)
+    }
+
+    /// Where source line `line` is in the target, for lines in and out of
+    /// hunks
+    ///
+    /// ```
+    /// use unidiff::{LineMapping, PatchSet};
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -2,2 +2,3 @@\n b\n-c\n+C\n+D\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(LineMapping::Unchanged(2), patch[0].map_source_to_target(2));
+    /// assert_eq!(LineMapping::Removed(3), patch[0].map_source_to_target(3));
+    /// assert_eq!(LineMapping::Unchanged(11), patch[0].map_source_to_target(10));
+    /// ```
+    pub fn map_source_to_target(&self, line: usize) -> LineMapping {
+        self.map_line(line, true)
+    }
+
+    /// Where target line `line` is in the source, for lines in and out of
+    /// hunks
+    pub fn map_target_to_source(&self, line: usize) -> LineMapping {
+        self.map_line(line, false)
+    }
+}
@@ -0,0 +1,180 @@
+//! Detecting code moved within a patch set, like `git diff --color-moved`
+
+use std::collections::HashMap;
+
+use crate::{LineType, PatchSet};
+
+/// How whitespace is compared between lines, as for moved lines with
+/// `git diff --color-moved-ws`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WhitespaceMode {
+    /// Lines must be identical
+    Exact,
+    /// Ignore whitespace at the end of lines
+    IgnoreSpaceAtEol,
+    /// Ignore changes in the amount of whitespace
+    IgnoreSpaceChange,
+    /// Ignore all whitespace
+    IgnoreAllSpace,
+    /// Ignore indentation, as long as it changes by the same amount on all
+    /// the lines of a block
+    AllowIndentationChange,
+}
+
+impl WhitespaceMode {
+    /// `value` as compared under this mode
+    pub(crate) fn normalize(self, value: &str) -> String {
+        match self {
+            WhitespaceMode::Exact => value.to_owned(),
+            WhitespaceMode::IgnoreSpaceAtEol => value.trim_end().to_owned(),
+            WhitespaceMode::IgnoreSpaceChange => {
+                value.split_whitespace().collect::<Vec<_>>().join(" ")
+            }
+            WhitespaceMode::IgnoreAllSpace => {
+                value.chars().filter(|c| !c.is_whitespace()).collect()
+            }
+            WhitespaceMode::AllowIndentationChange => value.trim().to_owned(),
+        }
+    }
+}
+
+/// Options of `PatchSet::detect_moves`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MoveOptions {
+    /// Count of lines a block needs to count as moved
+    pub min_lines: usize,
+    /// How whitespace is compared
+    pub whitespace: WhitespaceMode,
+}
+
+impl Default for MoveOptions {
+    fn default() -> MoveOptions {
+        MoveOptions {
+            min_lines: 3,
+            whitespace: WhitespaceMode::Exact,
+        }
+    }
+}
+
+/// A removed or added line of the patch set
+struct Changed {
+    /// File, hunk and line indexes
+    at: (usize, usize, usize),
+    /// Index of the run of lines of the same type it belongs to
+    run: usize,
+    /// Value as compared
+    key: String,
+    /// Width of the indentation
+    indent: usize,
+}
+
+fn indent(value: &str) -> usize {
+    value.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+impl PatchSet {
+    /// Mark the blocks of removed lines added back elsewhere in the patch
+    /// set, in the same file or another one, setting the `move_id` of the
+    /// lines of both sides of each block
+    ///
+    /// Blocks are numbered from 0 in the order of their added lines and
+    /// are matched longest first. Returns the count of blocks.
+    ///
+    /// ```
+    /// use unidiff::{MoveOptions, PatchSet};
+    ///
+    /// let mut patch: PatchSet = "--- a/a.rs\n+++ b/a.rs\n@@ -1,3 +0,0 @@\n-fn f() {\n-    g();\n-}\n\
+    ///                            --- a/b.rs\n+++ b/b.rs\n@@ -1 +1,4 @@\n x\n+fn f() {\n+    g();\n+}\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(1, patch.detect_moves(&MoveOptions::default()));
+    /// assert_eq!(Some(0), patch[0][0][0].move_id);
+    /// assert_eq!(Some(0), patch[1][0][3].move_id);
+    /// assert_eq!(None, patch[1][0][0].move_id);
+    /// ```
+    pub fn detect_moves(&mut self, options: &MoveOptions) -> usize {
+        let mut removed = vec![];
+        let mut added = vec![];
+        let mut run = 0;
+        for (file_idx, file) in self.files.iter_mut().enumerate() {
+            for (hunk_idx, hunk) in file.hunks.iter_mut().enumerate() {
+                let mut previous = LineType::Context;
+                for (line_idx, line) in hunk.lines.iter_mut().enumerate() {
+                    line.move_id = None;
+                    // "no newline" markers do not break runs
+                    if line.line_type == LineType::Empty {
+                        continue;
+                    }
+                    if line.line_type != previous {
+                        run += 1;
+                        previous = line.line_type.clone();
+                    }
+                    let changed = Changed {
+                        at: (file_idx, hunk_idx, line_idx),
+                        run,
+                        key: options.whitespace.normalize(&line.value),
+                        indent: indent(&line.value),
+                    };
+                    match line.line_type {
+                        LineType::Removed => removed.push(changed),
+                        LineType::Added => added.push(changed),
+                        _ => {}
+                    }
+                }
+                run += 1;
+            }
+        }
+
+        let mut by_key: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (idx, line) in removed.iter().enumerate() {
+            by_key.entry(&line.key).or_default().push(idx);
+        }
+        let allow_indentation = options.whitespace == WhitespaceMode::AllowIndentationChange;
+        // do two lines match, given the indentation change of their block
+        let matches = |r: &Changed, a: &Changed, delta: isize| {
+            r.key == a.key
+                && (!allow_indentation
+                    || r.key.is_empty()
+                    || a.indent as isize - r.indent as isize == delta)
+        };
+
+        let mut used = vec![false; removed.len()];
+        let mut count = 0;
+        let mut a = 0;
+        while a < added.len() {
+            let mut best: Option<(usize, usize)> = None;
+            for &r in by_key
+                .get(added[a].key.as_str())
+                .map_or(&[][..], |v| &v[..])
+            {
+                let delta = added[a].indent as isize - removed[r].indent as isize;
+                let mut len = 0;
+                while a + len < added.len()
+                    && r + len < removed.len()
+                    && !used[r + len]
+                    && added[a + len].run == added[a].run
+                    && removed[r + len].run == removed[r].run
+                    && matches(&removed[r + len], &added[a + len], delta)
+                {
+                    len += 1;
+                }
+                if len > best.map_or(0, |(_, best_len)| best_len) {
+                    best = Some((r, len));
+                }
+            }
+            match best {
+                Some((r, len)) if len >= options.min_lines => {
+                    for line in removed[r..r + len].iter().chain(&added[a..a + len]) {
+                        let (file_idx, hunk_idx, line_idx) = line.at;
+                        self.files[file_idx].hunks[hunk_idx].lines[line_idx].move_id = Some(count);
+                    }
+                    used[r..r + len].iter_mut().for_each(|u| *u = true);
+                    count += 1;
+                    a += len;
+                }
+                _ => a += 1,
+            }
+        }
+        count
+    }
+}
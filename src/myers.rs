@@ -0,0 +1,193 @@
+//! Myers' O(ND) difference algorithm, for diffing lines, words or characters
+//!
+//! This is the linear space variant, which splits the sequences at the
+//! middle snake of the shortest edit script, as git's xdiff does. Past a
+//! cost of about the square root of the input size the split is made at the
+//! furthest point reached instead, so unrelated inputs get a valid, if not
+//! minimal, script in bounded time.
+
+/// Least edit cost past which the search settles for an approximate split
+const MIN_MAX_COST: usize = 256;
+
+/// One step of an edit script, with indexes into the old and new sequences
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Shortest edit script turning `old` into `new`
+pub(crate) fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let size = old.len() + new.len() + 3;
+    let mut myers = Myers {
+        old,
+        new,
+        forward: vec![0; size],
+        backward: vec![0; size],
+        max_cost: ((size as f64).sqrt() as usize).max(MIN_MAX_COST),
+        edits: Vec::with_capacity(old.len().max(new.len())),
+    };
+    myers.compare(0, old.len(), 0, new.len());
+    myers.edits
+}
+
+/// State of a diff: the sequences, the furthest reaching paths of both
+/// directions by diagonal and the edits found so far
+struct Myers<'a, T> {
+    old: &'a [T],
+    new: &'a [T],
+    forward: Vec<isize>,
+    backward: Vec<isize>,
+    max_cost: usize,
+    edits: Vec<Edit>,
+}
+
+impl<'a, T: PartialEq> Myers<'a, T> {
+    /// Append the edits turning `old[x0..x1]` into `new[y0..y1]`
+    fn compare(&mut self, mut x0: usize, mut x1: usize, mut y0: usize, mut y1: usize) {
+        // common suffixes, in the order they were found, to append last
+        let mut suffixes = vec![];
+        loop {
+            while x0 < x1 && y0 < y1 && self.old[x0] == self.new[y0] {
+                self.edits.push(Edit::Equal(x0, y0));
+                x0 += 1;
+                y0 += 1;
+            }
+            let end = (x1, y1);
+            while x0 < x1 && y0 < y1 && self.old[x1 - 1] == self.new[y1 - 1] {
+                x1 -= 1;
+                y1 -= 1;
+            }
+            suffixes.push((x1, y1, end.0 - x1));
+            if x0 == x1 {
+                self.edits.extend((y0..y1).map(Edit::Insert));
+                break;
+            }
+            if y0 == y1 {
+                self.edits.extend((x0..x1).map(Edit::Delete));
+                break;
+            }
+            // recurse into the first half only, so that the depth stays low
+            // even when the search settles for approximate splits
+            let (x, y) = self.split(x0, x1, y0, y1);
+            self.compare(x0, x, y0, y);
+            x0 = x;
+            y0 = y;
+        }
+        for (x, y, len) in suffixes.into_iter().rev() {
+            self.edits
+                .extend((0..len).map(|idx| Edit::Equal(x + idx, y + idx)));
+        }
+    }
+
+    /// Point where an edit script turning `old[x0..x1]` into `new[y0..y1]`,
+    /// which differ at both ends, crosses the middle of its cost
+    fn split(&mut self, x0: usize, x1: usize, y0: usize, y1: usize) -> (usize, usize) {
+        let (x0, x1, y0, y1) = (x0 as isize, x1 as isize, y0 as isize, y1 as isize);
+        // diagonals are `x - y`, stored from `offset` past the lowest one,
+        // with a slot on each side for the sentinels
+        let (min_k, max_k) = (x0 - y1, x1 - y0);
+        let offset = 1 - min_k;
+        let at = |k: isize| (k + offset) as usize;
+        let (forward_mid, backward_mid) = (x0 - y0, x1 - y1);
+        let odd = (forward_mid - backward_mid) & 1 != 0;
+        let (mut forward_min, mut forward_max) = (forward_mid, forward_mid);
+        let (mut backward_min, mut backward_max) = (backward_mid, backward_mid);
+        self.forward[at(forward_mid)] = x0;
+        self.backward[at(backward_mid)] = x1;
+
+        let mut cost = 0;
+        loop {
+            cost += 1;
+            if forward_min > min_k {
+                forward_min -= 1;
+                self.forward[at(forward_min - 1)] = -1;
+            } else {
+                forward_min += 1;
+            }
+            if forward_max < max_k {
+                forward_max += 1;
+                self.forward[at(forward_max + 1)] = -1;
+            } else {
+                forward_max -= 1;
+            }
+            for k in (forward_min..=forward_max).rev().step_by(2) {
+                let mut x = if self.forward[at(k - 1)] >= self.forward[at(k + 1)] {
+                    self.forward[at(k - 1)] + 1
+                } else {
+                    self.forward[at(k + 1)]
+                };
+                let mut y = x - k;
+                while x < x1 && y < y1 && self.old[x as usize] == self.new[y as usize] {
+                    x += 1;
+                    y += 1;
+                }
+                self.forward[at(k)] = x;
+                if odd && backward_min <= k && k <= backward_max && self.backward[at(k)] <= x {
+                    return (x as usize, y as usize);
+                }
+            }
+
+            if backward_min > min_k {
+                backward_min -= 1;
+                self.backward[at(backward_min - 1)] = isize::MAX;
+            } else {
+                backward_min += 1;
+            }
+            if backward_max < max_k {
+                backward_max += 1;
+                self.backward[at(backward_max + 1)] = isize::MAX;
+            } else {
+                backward_max -= 1;
+            }
+            for k in (backward_min..=backward_max).rev().step_by(2) {
+                let mut x = if self.backward[at(k - 1)] < self.backward[at(k + 1)] {
+                    self.backward[at(k - 1)]
+                } else {
+                    self.backward[at(k + 1)] - 1
+                };
+                let mut y = x - k;
+                while x > x0 && y > y0 && self.old[x as usize - 1] == self.new[y as usize - 1] {
+                    x -= 1;
+                    y -= 1;
+                }
+                self.backward[at(k)] = x;
+                if !odd && forward_min <= k && k <= forward_max && x <= self.forward[at(k)] {
+                    return (x as usize, y as usize);
+                }
+            }
+
+            if cost < self.max_cost {
+                continue;
+            }
+            // too costly: split where either direction got furthest
+            let mut forward_best = (-1, 0);
+            for k in (forward_min..=forward_max).rev().step_by(2) {
+                let mut x = self.forward[at(k)].min(x1);
+                if x - k > y1 {
+                    x = y1 + k;
+                }
+                if x + (x - k) > forward_best.0 {
+                    forward_best = (x + (x - k), x);
+                }
+            }
+            let mut backward_best = (isize::MAX, 0);
+            for k in (backward_min..=backward_max).rev().step_by(2) {
+                let mut x = self.backward[at(k)].max(x0);
+                if x - k < y0 {
+                    x = y0 + k;
+                }
+                if x + (x - k) < backward_best.0 {
+                    backward_best = (x + (x - k), x);
+                }
+            }
+            let (sum, x) = if (x1 + y1) - backward_best.0 < forward_best.0 - (x0 + y0) {
+                forward_best
+            } else {
+                backward_best
+            };
+            return (x as usize, (sum - x) as usize);
+        }
+    }
+}
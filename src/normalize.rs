@@ -0,0 +1,217 @@
+//! Comparing patches by their changes only, whatever their line numbers,
+//! context and timestamps
+
+use crate::myers::{self, Edit};
+use crate::sha1::Sha1;
+use crate::{Hunk, LineType, PatchSet, PatchedFile, WhitespaceMode};
+
+/// Header lines that change a file beyond its content
+const SEMANTIC_HEADERS: &[&str] = &[
+    "new file mode ",
+    "deleted file mode ",
+    "old mode ",
+    "new mode ",
+    "rename from ",
+    "rename to ",
+    "copy from ",
+    "copy to ",
+];
+
+/// Removed and added lines of a run of changes, as compared
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Change {
+    removed: Vec<String>,
+    added: Vec<String>,
+}
+
+/// Changes of a file without line numbers, context or timestamps
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct NormalizedFile {
+    source: Option<String>,
+    target: Option<String>,
+    header: Vec<String>,
+    properties: Vec<(String, Option<String>, Option<String>)>,
+    changes: Vec<Change>,
+}
+
+/// A patch set reduced to its changes, for comparing and deduplicating
+/// patches whatever their line numbers, context sizes, timestamps and file
+/// order
+///
+/// Build it with `PatchSet::normalize`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NormalizedPatch {
+    files: Vec<NormalizedFile>,
+}
+
+/// Runs of changes of `hunk`, with lines compared under `whitespace` and
+/// those removed and added back dropped
+fn hunk_changes(hunk: &Hunk, whitespace: WhitespaceMode) -> Vec<Change> {
+    let mut changes = vec![];
+    let mut removed: Vec<String> = vec![];
+    let mut added: Vec<String> = vec![];
+    let mut previous = None;
+    // a trailing context line ends the last run
+    for line in hunk.lines.iter().map(Some).chain(Some(None)) {
+        match line {
+            Some(line) if line.is_removed() => {
+                removed.push(whitespace.normalize(&line.value));
+                previous = Some(LineType::Removed);
+                continue;
+            }
+            Some(line) if line.is_added() => {
+                added.push(whitespace.normalize(&line.value));
+                previous = Some(LineType::Added);
+                continue;
+            }
+            // a missing newline is a change, unless whitespace is ignored
+            Some(line) if !line.is_context() => {
+                let last = match previous {
+                    Some(LineType::Removed) => removed.last_mut(),
+                    Some(LineType::Added) => added.last_mut(),
+                    _ => None,
+                };
+                if let Some(last) = last.filter(|_| whitespace == WhitespaceMode::Exact) {
+                    last.push_str("\n\\");
+                }
+                continue;
+            }
+            _ => previous = None,
+        }
+        if removed.is_empty() && added.is_empty() {
+            continue;
+        }
+        let mut change = Change {
+            removed: vec![],
+            added: vec![],
+        };
+        for edit in myers::diff(&removed, &added) {
+            match edit {
+                Edit::Equal(..) => {}
+                Edit::Delete(x) => change.removed.push(removed[x].clone()),
+                Edit::Insert(y) => change.added.push(added[y].clone()),
+            }
+        }
+        if !change.removed.is_empty() || !change.added.is_empty() {
+            changes.push(change);
+        }
+        removed.clear();
+        added.clear();
+    }
+    changes
+}
+
+impl PatchedFile {
+    fn normalize(&self, whitespace: WhitespaceMode) -> NormalizedFile {
+        let binary = self.is_binary();
+        let header = self
+            .header
+            .iter()
+            .filter(|l| {
+                SEMANTIC_HEADERS.iter().any(|h| l.starts_with(h))
+                    || (binary && l.starts_with("index "))
+            })
+            .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect();
+        let properties = self
+            .properties
+            .iter()
+            .map(|p| (p.name.clone(), p.old_value(), p.new_value()))
+            .collect();
+        NormalizedFile {
+            source: self.source_path(),
+            target: self.target_path(),
+            header,
+            properties,
+            changes: self
+                .hunks
+                .iter()
+                .flat_map(|h| hunk_changes(h, whitespace))
+                .collect(),
+        }
+    }
+}
+
+impl NormalizedFile {
+    /// Does this file change anything
+    fn is_change(&self) -> bool {
+        !self.changes.is_empty()
+            || !self.header.is_empty()
+            || !self.properties.is_empty()
+            || self.source != self.target
+    }
+}
+
+impl NormalizedPatch {
+    /// Hash of the changes, the same across runs and platforms, as 40
+    /// hexadecimal digits
+    pub fn content_hash(&self) -> String {
+        let mut sha1 = Sha1::new();
+        let mut field = |value: &str| {
+            sha1.update(&(value.len() as u64).to_be_bytes());
+            sha1.update(value.as_bytes());
+        };
+        for file in &self.files {
+            field("file");
+            field(file.source.as_deref().unwrap_or("/dev/null"));
+            field(file.target.as_deref().unwrap_or("/dev/null"));
+            for line in &file.header {
+                field(line);
+            }
+            for (name, old, new) in &file.properties {
+                field("property");
+                field(name);
+                field(old.as_deref().unwrap_or("\0"));
+                field(new.as_deref().unwrap_or("\0"));
+            }
+            for change in &file.changes {
+                field("change");
+                for line in &change.removed {
+                    field("-");
+                    field(line);
+                }
+                for line in &change.added {
+                    field("+");
+                    field(line);
+                }
+            }
+        }
+        sha1.finish().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+impl PatchSet {
+    /// The changes of this patch set without line numbers, context,
+    /// timestamps or file order, with lines compared under `whitespace`
+    ///
+    /// Lines removed and added back in the same run of changes cancel out,
+    /// and files left without changes are dropped.
+    ///
+    /// ```
+    /// use unidiff::{PatchSet, WhitespaceMode};
+    ///
+    /// let patch: PatchSet = "--- a/f\t2024-01-01\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n-b\n+c\n d\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let other: PatchSet = "--- a/f\n+++ b/f\n@@ -12 +12 @@\n-b\n+c\n".parse().unwrap();
+    /// let exact = WhitespaceMode::Exact;
+    /// assert_eq!(patch.normalize(exact), other.normalize(exact));
+    /// assert_eq!(patch.normalize(exact).content_hash(), other.normalize(exact).content_hash());
+    /// ```
+    pub fn normalize(&self, whitespace: WhitespaceMode) -> NormalizedPatch {
+        let mut files: Vec<NormalizedFile> = self
+            .files
+            .iter()
+            .map(|f| f.normalize(whitespace))
+            .filter(NormalizedFile::is_change)
+            .collect();
+        files.sort();
+        NormalizedPatch { files }
+    }
+
+    /// Do both patch sets make the same changes, whatever their line
+    /// numbers, context, timestamps and file order
+    pub fn is_equivalent(&self, other: &PatchSet, whitespace: WhitespaceMode) -> bool {
+        self.normalize(whitespace) == other.normalize(whitespace)
+    }
+}
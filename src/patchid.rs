@@ -0,0 +1,189 @@
+//! Ids of changes that ignore whitespace and line numbers, like
+//! `git patch-id`
+
+use std::fmt;
+
+use crate::sha1::Sha1;
+use crate::{PatchSet, PatchedFile, RE_HUNK_HEADER};
+
+/// Id of the changes of a patch, the same for patches that only differ in
+/// whitespace and line numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PatchId(pub [u8; 20]);
+
+impl fmt::Display for PatchId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Count of lines of each side of the hunk header `line`
+fn hunk_lengths(line: &str) -> (isize, isize) {
+    let captures = RE_HUNK_HEADER.captures(line).unwrap();
+    let length = |name| {
+        captures
+            .name(name)
+            .map_or(1, |m| m.as_str().parse().unwrap_or(0))
+    };
+    (length("source_length"), length("target_length"))
+}
+
+/// Hashes of the diff text, added up as git does
+struct PatchIdHasher {
+    result: [u8; 20],
+    sha1: Sha1,
+    stable: bool,
+    hashed: usize,
+}
+
+impl PatchIdHasher {
+    /// Add the hash of the lines since the last flush to the result, with
+    /// carry, so that the order of the files does not matter
+    fn flush(&mut self) {
+        let hash = std::mem::replace(&mut self.sha1, Sha1::new()).finish();
+        let mut carry = 0u16;
+        for (result, byte) in self.result.iter_mut().zip(&hash) {
+            carry += u16::from(*result) + u16::from(*byte);
+            *result = carry as u8;
+            carry >>= 8;
+        }
+    }
+
+    fn update(&mut self, line: &str) {
+        let line: String = line
+            .chars()
+            .filter(|c| !(c.is_ascii_whitespace() || *c == '\x0b'))
+            .collect();
+        self.hashed += line.len();
+        self.sha1.update(line.as_bytes());
+    }
+
+    /// Hash the lines of a diff, as git's `get_one_patchid`
+    fn hash<'a>(mut self, lines: impl Iterator<Item = &'a str>) -> Option<PatchId> {
+        // lines left in each side of the hunk, -1 in file headers
+        let (mut before, mut after) = (-1isize, -1isize);
+        let mut binary = false;
+        let (mut pre_id, mut post_id) = (String::new(), String::new());
+        for line in lines {
+            if line.starts_with("\\ ") && line.len() > 11 {
+                continue;
+            }
+            // skip the commit message
+            if self.hashed == 0 && !line.starts_with("diff ") {
+                continue;
+            }
+            if before == -1 {
+                if line.starts_with("GIT binary patch") || line.starts_with("Binary files") {
+                    binary = true;
+                    before = 0;
+                    self.sha1.update(pre_id.as_bytes());
+                    self.sha1.update(post_id.as_bytes());
+                    if self.stable {
+                        self.flush();
+                    }
+                    continue;
+                } else if let Some(ids) = line.strip_prefix("index ") {
+                    if let Some((pre, rest)) = ids.split_once("..") {
+                        pre_id = pre.to_owned();
+                        post_id = rest.split(' ').next().unwrap_or("").to_owned();
+                    }
+                    continue;
+                } else if line.starts_with("--- ") {
+                    before = 1;
+                    after = 1;
+                } else if !line.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                    break;
+                }
+            }
+            if binary {
+                if line.starts_with("diff ") {
+                    binary = false;
+                    before = -1;
+                }
+                continue;
+            }
+            if before == 0 && after == 0 {
+                if line.starts_with("@@ -") {
+                    let (source, target) = hunk_lengths(line);
+                    before = source;
+                    after = target;
+                    continue;
+                }
+                // the end of the patch
+                if !line.starts_with("diff ") {
+                    break;
+                }
+                if self.stable {
+                    self.flush();
+                }
+                before = -1;
+                after = -1;
+            }
+            if line.starts_with('-') || line.starts_with(' ') {
+                before -= 1;
+            }
+            if line.starts_with('+') || line.starts_with(' ') {
+                after -= 1;
+            }
+            self.update(line);
+        }
+        if self.hashed == 0 {
+            return None;
+        }
+        self.flush();
+        Some(PatchId(self.result))
+    }
+}
+
+/// Patch id of the diff `text`, `None` when it has no `diff` line
+fn patch_id(text: &str, stable: bool) -> Option<PatchId> {
+    let hasher = PatchIdHasher {
+        result: [0; 20],
+        sha1: Sha1::new(),
+        stable,
+        hashed: 0,
+    };
+    hasher.hash(text.lines())
+}
+
+impl PatchedFile {
+    /// Patch id of this file's changes, like `git patch-id`
+    ///
+    /// `None` when the file has no `diff` line in its header, as git only
+    /// hashes what follows one.
+    pub fn patch_id(&self) -> Option<PatchId> {
+        patch_id(&self.to_string(), false)
+    }
+}
+
+impl PatchSet {
+    /// Patch id of the changes, like `git patch-id --unstable`, which
+    /// depends on the order of the files
+    ///
+    /// `None` when there is no `diff` line, as git only hashes what follows
+    /// one.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let moved: PatchSet = "diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -8,2 +8,2 @@\n  a\n-b\n+c  \n"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(patch.patch_id(), moved.patch_id());
+    /// ```
+    pub fn patch_id(&self) -> Option<PatchId> {
+        patch_id(&self.to_string(), false)
+    }
+
+    /// Patch id of the changes, like `git patch-id --stable`, the same
+    /// whatever the order of the files
+    pub fn stable_patch_id(&self) -> Option<PatchId> {
+        patch_id(&self.to_string(), true)
+    }
+}
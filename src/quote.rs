@@ -0,0 +1,115 @@
+//! C style quoting of file names, as done by git
+
+/// Quote `name` the way git does when it contains special characters
+pub(crate) fn quote(name: &str) -> String {
+    if !name.bytes().any(needs_quoting) {
+        return name.to_owned();
+    }
+    escape(name)
+}
+
+/// Quote `name` when it was quoted in the diff it was read from, or when it
+/// cannot be written unquoted
+///
+/// Other tools, and git with `core.quotePath` off, write non-ASCII names as
+/// they are.
+pub(crate) fn requote(name: &str, quoted: bool) -> String {
+    if !quoted && !name.bytes().any(must_quote) {
+        return name.to_owned();
+    }
+    escape(name)
+}
+
+fn escape(name: &str) -> String {
+    let mut quoted = String::with_capacity(name.len() + 2);
+    quoted.push('"');
+    for b in name.bytes() {
+        match b {
+            0x07 => quoted.push_str("\\a"),
+            0x08 => quoted.push_str("\\b"),
+            b'\t' => quoted.push_str("\\t"),
+            b'\n' => quoted.push_str("\\n"),
+            0x0b => quoted.push_str("\\v"),
+            0x0c => quoted.push_str("\\f"),
+            b'\r' => quoted.push_str("\\r"),
+            b'"' => quoted.push_str("\\\""),
+            b'\\' => quoted.push_str("\\\\"),
+            b if needs_quoting(b) => quoted.push_str(&format!("\\{:03o}", b)),
+            b => quoted.push(b as char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn needs_quoting(b: u8) -> bool {
+    must_quote(b) || b >= 0x80
+}
+
+/// Bytes no name can be written with unquoted
+fn must_quote(b: u8) -> bool {
+    b == b'"' || b == b'\\' || b < 0x20 || b == 0x7f
+}
+
+/// Unquote a C style quoted name, returning other names unchanged
+pub(crate) fn unquote(name: &str) -> String {
+    let inner = match name.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+        Some(inner) => inner,
+        None => return name.to_owned(),
+    };
+    let bytes = inner.as_bytes();
+    let mut unquoted = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let b = bytes[idx];
+        idx += 1;
+        if b != b'\\' || idx == bytes.len() {
+            unquoted.push(b);
+            continue;
+        }
+        let escaped = bytes[idx];
+        idx += 1;
+        let b = match escaped {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => 0x0b,
+            b'f' => 0x0c,
+            b'r' => b'\r',
+            b'0'..=b'7' => {
+                let mut value = u32::from(escaped - b'0');
+                for _ in 0..2 {
+                    match bytes.get(idx) {
+                        Some(&d @ b'0'..=b'7') => {
+                            value = value * 8 + u32::from(d - b'0');
+                            idx += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                value as u8
+            }
+            other => other,
+        };
+        unquoted.push(b);
+    }
+    String::from_utf8_lossy(&unquoted).into_owned()
+}
+
+/// Split a leading, possibly quoted, name off `text`
+pub(crate) fn split_name(text: &str) -> Option<(&str, &str)> {
+    if !text.starts_with('"') {
+        return None;
+    }
+    let bytes = text.as_bytes();
+    let mut idx = 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            b'"' => return Some((&text[..=idx], &text[idx + 1..])),
+            _ => idx += 1,
+        }
+    }
+    None
+}
@@ -0,0 +1,312 @@
+//! Moving a patch on top of another one made against the same base, and
+//! finding where two such patches conflict
+
+use crate::edit::build_hunk;
+use crate::{Hunk, Line, PatchSet, PatchedFile};
+
+/// How the changes of two conflicting hunks relate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both hunks make the same change at the same place
+    Identical,
+    /// The hunks change some of the same lines, or insert lines at the same
+    /// place
+    Overlapping,
+    /// The changes of one hunk touch those of the other or fall in its
+    /// context lines, without overlapping them
+    Adjacent,
+}
+
+/// Hunks of two patch sets that cannot be applied one after the other
+///
+/// Changes to a binary file, or to the mode or name of a file, conflict as
+/// a whole: they have no hunk index and an empty range at line 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// Path of the file both patch sets change
+    pub path: String,
+    /// How the changes of both hunks relate
+    pub kind: ConflictKind,
+    /// Index of the file in the first patch set
+    pub file_index: usize,
+    /// Index of the hunk in that file, `None` when the whole file conflicts
+    pub hunk_index: Option<usize>,
+    /// Source lines from the first to past the last line the hunk changes,
+    /// empty at the insertion point for hunks that only add lines
+    pub range: (usize, usize),
+    /// Index of the file in the other patch set
+    pub other_file_index: usize,
+    /// Index of the hunk in that file, `None` when the whole file conflicts
+    pub other_hunk_index: Option<usize>,
+    /// Source lines the hunk of the other patch set changes
+    pub other_range: (usize, usize),
+}
+
+impl Hunk {
+    /// Source lines from the first to the last changed line, empty at the
+    /// insertion point for hunks that only add lines
+    pub(crate) fn changed_range(&self) -> (usize, usize) {
+        let positions = self.line_positions();
+        let changed = |l: &Line| l.is_added() || l.is_removed();
+        let first = self.lines.iter().position(changed);
+        let last = self.lines.iter().rposition(changed);
+        match (first, last) {
+            (Some(first), Some(last)) => (positions[first].0, positions[last + 1].0),
+            _ => (positions[0].0, positions[0].0),
+        }
+    }
+
+    /// All the source lines of this hunk, context included
+    pub(crate) fn source_range(&self) -> (usize, usize) {
+        let (start, _) = self.start_positions();
+        (start, start + self.source_length)
+    }
+}
+
+/// Removed and added lines of a hunk, without context
+fn changed_lines(hunk: &Hunk) -> impl Iterator<Item = &Line> {
+    hunk.lines.iter().filter(|l| l.is_added() || l.is_removed())
+}
+
+/// How the changes of `hunk` and `other`, made against the same file,
+/// conflict, if they do
+fn classify(hunk: &Hunk, other: &Hunk) -> Option<ConflictKind> {
+    let (changed, other_changed) = (hunk.changed_range(), other.changed_range());
+    let same_lines = changed_lines(hunk)
+        .map(|l| (&l.line_type, &l.value))
+        .eq(changed_lines(other).map(|l| (&l.line_type, &l.value)));
+    if changed == other_changed && same_lines {
+        Some(ConflictKind::Identical)
+    } else if overlaps(changed, other_changed) {
+        Some(ConflictKind::Overlapping)
+    } else if changed.1 == other_changed.0
+        || other_changed.1 == changed.0
+        || overlaps(hunk.source_range(), other_changed)
+        || overlaps(other.source_range(), changed)
+    {
+        Some(ConflictKind::Adjacent)
+    } else {
+        None
+    }
+}
+
+/// Do two ranges of lines overlap, an empty range being the point between
+/// two lines
+pub(crate) fn overlaps(a: (usize, usize), b: (usize, usize)) -> bool {
+    match (a.0 == a.1, b.0 == b.1) {
+        (true, true) => a.0 == b.0,
+        (true, false) => b.0 < a.0 && a.0 < b.1,
+        (false, true) => a.0 < b.0 && b.0 < a.1,
+        (false, false) => a.0 < b.1 && b.0 < a.1,
+    }
+}
+
+impl PatchedFile {
+    /// Path two patches against the same tree share for this file: the
+    /// source path, or the target path for an added file
+    fn matched_path(&self) -> Option<String> {
+        self.source_path().or_else(|| self.target_path())
+    }
+
+    /// Do this patch and `other`, made against the same file, make
+    /// different changes that hunks do not show: to a binary file, to the
+    /// mode of the file or to its name
+    fn conflicts_as_file(&self, other: &PatchedFile) -> bool {
+        let renamed_to = |f: &PatchedFile| {
+            let (source, target) = (f.source_path(), f.target_path());
+            target.filter(|t| source.is_some_and(|s| s != *t))
+        };
+        if let (Some(name), Some(other_name)) = (renamed_to(self), renamed_to(other)) {
+            if name != other_name {
+                return true;
+            }
+        }
+        let no_hunks_show = |f: &PatchedFile| f.is_binary() || f.is_header_only();
+        (no_hunks_show(self) || no_hunks_show(other)) && self.header != other.header
+    }
+
+    /// The conflict of this file, at `file_index`, with `other`, at
+    /// `other_file_index`, over hunks at `indexes` or as a whole
+    fn conflict(
+        &self,
+        file_index: usize,
+        other: &PatchedFile,
+        other_file_index: usize,
+        kind: ConflictKind,
+        indexes: Option<(usize, usize)>,
+    ) -> Conflict {
+        let range = |f: &PatchedFile, idx: Option<usize>| {
+            idx.map_or((0, 0), |idx| f.hunks[idx].changed_range())
+        };
+        Conflict {
+            path: self.path(),
+            kind,
+            file_index,
+            hunk_index: indexes.map(|i| i.0),
+            range: range(self, indexes.map(|i| i.0)),
+            other_file_index,
+            other_hunk_index: indexes.map(|i| i.1),
+            other_range: range(other, indexes.map(|i| i.1)),
+        }
+    }
+
+    /// This patch moved on top of `onto`, both made against the same file,
+    /// or the indexes of the hunks of both that conflict
+    fn rebase_hunks(
+        &self,
+        onto: &PatchedFile,
+    ) -> Result<Vec<Hunk>, Vec<(usize, usize, ConflictKind)>> {
+        let mut conflicts = vec![];
+        let mut hunks = Vec::with_capacity(self.hunks.len());
+        for (idx, hunk) in self.hunks.iter().enumerate() {
+            let range = hunk.source_range();
+            let mut offset = 0isize;
+            for (other_idx, other) in onto.hunks.iter().enumerate() {
+                if let Some(kind) = classify(hunk, other) {
+                    conflicts.push((idx, other_idx, kind));
+                } else if other.changed_range().1 <= range.0 {
+                    offset += other.target_length as isize - other.source_length as isize;
+                }
+            }
+            let (source_pos, target_pos) = hunk.start_positions();
+            hunks.push(build_hunk(
+                (source_pos as isize + offset) as usize,
+                (target_pos as isize + offset) as usize,
+                &hunk.section_header,
+                hunk.lines.clone(),
+            ));
+        }
+        if conflicts.is_empty() {
+            Ok(hunks)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+impl PatchSet {
+    /// This patch set moved on top of `onto`, both made against the same
+    /// tree, so that applying `onto` then the result gives both changes
+    ///
+    /// Hunks are shifted by the lines `onto` adds and removes before them.
+    /// Hunks conflict when their changes are identical to, overlap or are
+    /// adjacent to those of a hunk of `onto`, as reported by
+    /// [`conflicts`](PatchSet::conflicts).
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let ours: PatchSet = "--- a/f\n+++ b/f\n@@ -9,3 +9,3 @@\n i\n-j\n+J\n k\n".parse().unwrap();
+    /// let theirs: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1,3 @@\n a\n+b\n+c\n".parse().unwrap();
+    /// let rebased = ours.rebase(&theirs).unwrap();
+    /// assert_eq!((11, 11), (rebased[0][0].source_start, rebased[0][0].target_start));
+    ///
+    /// let clashing: PatchSet = "--- a/f\n+++ b/f\n@@ -10 +10 @@\n-j\n+jj\n".parse().unwrap();
+    /// assert_eq!(1, ours.rebase(&clashing).unwrap_err().len());
+    /// ```
+    pub fn rebase(&self, onto: &PatchSet) -> Result<PatchSet, Vec<Conflict>> {
+        let mut conflicts = vec![];
+        let mut files = Vec::with_capacity(self.files.len());
+        for (file_index, file) in self.files.iter().enumerate() {
+            let path = file.matched_path();
+            let other = onto
+                .files
+                .iter()
+                .position(|f| path.is_some() && f.matched_path() == path);
+            let other_file_index = match other {
+                Some(idx) => idx,
+                None => {
+                    files.push(file.clone());
+                    continue;
+                }
+            };
+            let other = &onto.files[other_file_index];
+            if file.conflicts_as_file(other) {
+                conflicts.push(file.conflict(
+                    file_index,
+                    other,
+                    other_file_index,
+                    ConflictKind::Overlapping,
+                    None,
+                ));
+            }
+            match file.rebase_hunks(other) {
+                Ok(hunks) => {
+                    let mut file = file.clone();
+                    file.hunks = hunks;
+                    files.push(file);
+                }
+                Err(pairs) => conflicts.extend(pairs.into_iter().map(|(idx, other_idx, kind)| {
+                    file.conflict(
+                        file_index,
+                        other,
+                        other_file_index,
+                        kind,
+                        Some((idx, other_idx)),
+                    )
+                })),
+            }
+        }
+        if conflicts.is_empty() {
+            Ok(self.with_files(files))
+        } else {
+            Err(conflicts)
+        }
+    }
+
+    /// Hunks of this patch set and `other`, both made against the same
+    /// tree, whose changes are identical, overlap or are adjacent
+    ///
+    /// Files are matched by their source path, or their target path when
+    /// added, so that both adding the same file conflict. Changes are
+    /// adjacent when they touch or fall in the context lines of the other
+    /// hunk. Different changes to a binary file, or to the mode or the name
+    /// of a file, overlap as a whole.
+    ///
+    /// ```
+    /// use unidiff::{ConflictKind, PatchSet};
+    ///
+    /// let ours: PatchSet = "--- a/f\n+++ b/f\n@@ -9,3 +9,3 @@\n i\n-j\n+J\n k\n".parse().unwrap();
+    /// let theirs: PatchSet = "--- a/f\n+++ b/f\n@@ -10 +10 @@\n-j\n+jj\n".parse().unwrap();
+    /// let conflicts = ours.conflicts(&theirs);
+    /// assert_eq!(ConflictKind::Overlapping, conflicts[0].kind);
+    /// assert_eq!(((10, 11), (10, 11)), (conflicts[0].range, conflicts[0].other_range));
+    /// ```
+    pub fn conflicts(&self, other: &PatchSet) -> Vec<Conflict> {
+        let mut conflicts = vec![];
+        for (file_index, file) in self.files.iter().enumerate() {
+            let path = file.matched_path();
+            if path.is_none() {
+                continue;
+            }
+            for (other_file_index, other_file) in other.files.iter().enumerate() {
+                if other_file.matched_path() != path {
+                    continue;
+                }
+                if file.conflicts_as_file(other_file) {
+                    conflicts.push(file.conflict(
+                        file_index,
+                        other_file,
+                        other_file_index,
+                        ConflictKind::Overlapping,
+                        None,
+                    ));
+                }
+                for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+                    for (other_hunk_index, other_hunk) in other_file.hunks.iter().enumerate() {
+                        if let Some(kind) = classify(hunk, other_hunk) {
+                            conflicts.push(file.conflict(
+                                file_index,
+                                other_file,
+                                other_file_index,
+                                kind,
+                                Some((hunk_index, other_hunk_index)),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+}
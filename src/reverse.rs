@@ -0,0 +1,150 @@
+//! Reversing patches, like `patch -R` or `interdiff -r`
+
+use crate::edit::build_hunk;
+use crate::quote;
+use crate::{Hunk, LineType, PatchSet, PatchedFile, PropertyAction, VcsHeader};
+
+/// Swap source and target names, keeping the `a/` and `b/` prefixes in place
+fn swap_names(source: &str, target: &str) -> (String, String) {
+    let source_name = match target.strip_prefix("b/") {
+        Some(name) => format!("a/{}", name),
+        None => target.to_owned(),
+    };
+    let target_name = match source.strip_prefix("a/") {
+        Some(name) => format!("b/{}", name),
+        None => source.to_owned(),
+    };
+    (source_name, target_name)
+}
+
+/// Reverse a git extended header line, such as `new file mode` or `index`
+fn reverse_header_line(line: &str) -> String {
+    const SWAPS: [(&str, &str); 3] = [
+        ("new file mode ", "deleted file mode "),
+        ("old mode ", "new mode "),
+        ("rename from ", "rename to "),
+    ];
+    for (a, b) in SWAPS.iter() {
+        if let Some(rest) = line.strip_prefix(a) {
+            return format!("{}{}", b, rest);
+        }
+        if let Some(rest) = line.strip_prefix(b) {
+            return format!("{}{}", a, rest);
+        }
+    }
+    if let Some(rest) = line.strip_prefix("index ") {
+        let (ids, mode) = rest
+            .split_once(' ')
+            .map_or((rest, None), |(i, m)| (i, Some(m)));
+        if let Some((old, new)) = ids.split_once("..") {
+            return match mode {
+                Some(mode) => format!("index {}..{} {}", new, old, mode),
+                None => format!("index {}..{}", new, old),
+            };
+        }
+    }
+    if let Some(VcsHeader::Git { source, target }) = VcsHeader::parse(line) {
+        let (source, target) = swap_names(&source, &target);
+        let quoted = line.contains('"');
+        return format!(
+            "diff --git {} {}",
+            quote::requote(&source, quoted),
+            quote::requote(&target, quoted)
+        );
+    }
+    line.to_owned()
+}
+
+impl Hunk {
+    /// The hunk undoing this one
+    ///
+    /// In each block of changes the removed lines come first, as diff
+    /// tools write them.
+    pub fn reverse(&self) -> Hunk {
+        let (source_pos, target_pos) = self.start_positions();
+        let mut lines = Vec::with_capacity(self.lines.len());
+        let (mut removed, mut added) = (vec![], vec![]);
+        let mut last = LineType::Context;
+        for line in &self.lines {
+            let mut line = line.clone();
+            let line_type = match line.line_type {
+                // a "no newline" marker goes with the line before it
+                LineType::Empty => last.clone(),
+                LineType::Added => LineType::Removed,
+                LineType::Removed => LineType::Added,
+                LineType::Context => LineType::Context,
+            };
+            if line.line_type != LineType::Empty {
+                line.line_type = line_type.clone();
+            }
+            match line_type {
+                LineType::Removed => removed.push(line),
+                LineType::Added => added.push(line),
+                _ => {
+                    lines.append(&mut removed);
+                    lines.append(&mut added);
+                    lines.push(line);
+                }
+            }
+            last = line_type;
+        }
+        lines.append(&mut removed);
+        lines.append(&mut added);
+        build_hunk(target_pos, source_pos, &self.section_header, lines)
+    }
+}
+
+impl PatchedFile {
+    /// The patch undoing this one, going from the target back to the source
+    pub fn reverse(&self) -> PatchedFile {
+        let mut header: Vec<String> = self.header.iter().map(|l| reverse_header_line(l)).collect();
+        // keep `old mode` before `new mode` and `rename from` before `rename to`
+        for idx in 1..header.len() {
+            let swap = (header[idx - 1].starts_with("new mode ")
+                && header[idx].starts_with("old mode "))
+                || (header[idx - 1].starts_with("rename to ")
+                    && header[idx].starts_with("rename from "));
+            if swap {
+                header.swap(idx - 1, idx);
+            }
+        }
+        let mut properties = self.properties.clone();
+        for property in &mut properties {
+            property.action = match property.action {
+                PropertyAction::Added => PropertyAction::Deleted,
+                PropertyAction::Deleted => PropertyAction::Added,
+                PropertyAction::Modified => PropertyAction::Modified,
+            };
+            property.hunks = property.hunks.iter().map(Hunk::reverse).collect();
+        }
+        let (source_file, target_file) = swap_names(&self.source_file, &self.target_file);
+        PatchedFile {
+            header,
+            source_file,
+            source_timestamp: self.target_timestamp.clone(),
+            source_revision: self.target_revision,
+            target_file,
+            target_timestamp: self.source_timestamp.clone(),
+            target_revision: self.source_revision,
+            properties,
+            hunks: self.hunks.iter().map(Hunk::reverse).collect(),
+            source_quoted: self.target_quoted,
+            target_quoted: self.source_quoted,
+        }
+    }
+}
+
+impl PatchSet {
+    /// The patch set undoing this one, like `patch -R`
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1 @@\n a\n-b\n".parse().unwrap();
+    /// let reverse = patch.reverse();
+    /// assert_eq!("--- a/f\n+++ b/f\n@@ -1,1 +1,2 @@ \n a\n+b", reverse.to_string());
+    /// ```
+    pub fn reverse(&self) -> PatchSet {
+        self.with_files(self.files.iter().map(PatchedFile::reverse).collect())
+    }
+}
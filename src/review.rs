@@ -0,0 +1,108 @@
+//! Positions of lines as code review APIs address them
+
+use std::fmt;
+
+use crate::{Line, LineType, PatchSet, PatchedFile};
+
+/// Side of a diff a review comment is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The source side, where removed lines are
+    Left,
+    /// The target side, where added lines are
+    Right,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Side::Left => write!(f, "LEFT"),
+            Side::Right => write!(f, "RIGHT"),
+        }
+    }
+}
+
+impl PatchedFile {
+    /// Lines of the hunks with their position in the file's diff: the
+    /// first line after the first `@@` header is 1 and the headers of the
+    /// following hunks take a position too, as on GitHub
+    fn positioned_lines(&self) -> impl Iterator<Item = (usize, &Line)> {
+        let mut position = 0;
+        self.hunks.iter().enumerate().flat_map(move |(idx, hunk)| {
+            if idx > 0 {
+                position += 1;
+            }
+            let start = position;
+            position += hunk.lines.len();
+            hunk.lines
+                .iter()
+                .enumerate()
+                .map(move |(offset, line)| (start + offset + 1, line))
+        })
+    }
+
+    /// Position in this file's diff of `line` on `side`, if a review
+    /// comment can be left on it
+    ///
+    /// Context lines can be commented on both sides.
+    ///
+    /// ```
+    /// use unidiff::{PatchSet, Side};
+    ///
+    /// let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n".parse().unwrap();
+    /// assert_eq!(Some(3), patch[0].position(Side::Right, 2));
+    /// assert_eq!(Some(2), patch[0].position(Side::Left, 2));
+    /// assert_eq!(None, patch[0].position(Side::Right, 5));
+    /// ```
+    pub fn position(&self, side: Side, line: usize) -> Option<usize> {
+        self.positioned_lines()
+            .find(|(_, l)| match side {
+                Side::Left => !l.is_added() && l.source_line_no == Some(line),
+                Side::Right => !l.is_removed() && l.target_line_no == Some(line),
+            })
+            .map(|(position, _)| position)
+    }
+
+    /// Side and line number at `position` in this file's diff, `None` for
+    /// hunk headers, "no newline" markers and positions past the end
+    ///
+    /// Context lines are on the right side.
+    pub fn line_at_position(&self, position: usize) -> Option<(Side, usize)> {
+        let (_, line) = self.positioned_lines().find(|&(p, _)| p == position)?;
+        match line.line_type {
+            LineType::Removed => line.source_line_no.map(|n| (Side::Left, n)),
+            LineType::Added | LineType::Context => line.target_line_no.map(|n| (Side::Right, n)),
+            LineType::Empty => None,
+        }
+    }
+
+    /// Can a review comment be left on `line` on `side`
+    pub fn is_commentable(&self, side: Side, line: usize) -> bool {
+        self.position(side, line).is_some()
+    }
+}
+
+impl PatchSet {
+    /// Index of the file and position in that file's diff of the line at
+    /// `diff_line_no` in the whole diff
+    pub fn position_of_diff_line(&self, diff_line_no: usize) -> Option<(usize, usize)> {
+        self.files
+            .iter()
+            .enumerate()
+            .find_map(|(file_index, file)| {
+                file.positioned_lines()
+                    .find(|(_, l)| l.diff_line_no == diff_line_no)
+                    .map(|(position, _)| (file_index, position))
+            })
+    }
+
+    /// Line number in the whole diff of `position` in the diff of the file
+    /// at `file_index`
+    pub fn diff_line_at_position(&self, file_index: usize, position: usize) -> Option<usize> {
+        self.files
+            .get(file_index)?
+            .positioned_lines()
+            .find(|&(p, _)| p == position)
+            .map(|(_, l)| l.diff_line_no)
+    }
+}
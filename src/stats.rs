@@ -0,0 +1,308 @@
+//! Change statistics aggregated over files, like `git diff --dirstat`
+
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+use crate::{Line, LineType, PatchSet, PatchedFile};
+
+/// Prefixes of comment lines in C-like languages, with the continuation
+/// lines of block comments
+const C_COMMENTS: &[&str] = &["//", "/*", "*/", "* "];
+/// Prefixes of comment lines in shell-like languages
+const HASH_COMMENTS: &[&str] = &["#"];
+/// Prefixes of comment lines in Python, with docstrings
+const PYTHON_COMMENTS: &[&str] = &["#", "\"\"\"", "'''"];
+/// Prefixes of comment lines in SQL, Lua and Haskell
+const DASH_COMMENTS: &[&str] = &["--"];
+/// Prefixes of comment lines in Lisps, assembly and ini files
+const SEMICOLON_COMMENTS: &[&str] = &[";"];
+/// Prefixes of comment lines in TeX and Erlang
+const PERCENT_COMMENTS: &[&str] = &["%"];
+/// Prefixes of comment lines in markup languages
+const MARKUP_COMMENTS: &[&str] = &["<!--"];
+
+/// Counts of the changes of one or more files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ChangeStats {
+    /// Count of files
+    pub files: usize,
+    /// Count of hunks
+    pub hunks: usize,
+    /// Count of lines added
+    pub added: usize,
+    /// Count of lines removed
+    pub removed: usize,
+    /// Count of lines added or removed in changes that only touch
+    /// whitespace: blank lines, indentation, line breaks
+    pub whitespace_only: usize,
+    /// Count of lines added or removed in changes that only touch comments
+    pub comment_only: usize,
+}
+
+impl ChangeStats {
+    /// Count of lines added and removed
+    pub fn churn(&self) -> usize {
+        self.added + self.removed
+    }
+}
+
+impl AddAssign for ChangeStats {
+    fn add_assign(&mut self, other: ChangeStats) {
+        self.files += other.files;
+        self.hunks += other.hunks;
+        self.added += other.added;
+        self.removed += other.removed;
+        self.whitespace_only += other.whitespace_only;
+        self.comment_only += other.comment_only;
+    }
+}
+
+fn without_whitespace(lines: &[&Line]) -> String {
+    lines
+        .iter()
+        .flat_map(|l| l.value.chars())
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+/// Prefixes of comment lines in the language of the file at `path`, by its
+/// extension or name, none for unknown files
+fn comment_prefixes(path: &str) -> &'static [&'static str] {
+    match extension(path).to_ascii_lowercase().as_str() {
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" | "m" | "mm" | "cs" | "java"
+        | "kt" | "kts" | "scala" | "groovy" | "gradle" | "swift" | "go" | "rs" | "js" | "mjs"
+        | "cjs" | "jsx" | "ts" | "tsx" | "dart" | "php" | "css" | "scss" | "less" | "proto"
+        | "zig" => C_COMMENTS,
+        "sh" | "bash" | "zsh" | "fish" | "rb" | "pl" | "pm" | "r" | "yml" | "yaml" | "toml"
+        | "cmake" | "mk" | "conf" | "cfg" | "ps1" | "tf" | "nix" => HASH_COMMENTS,
+        "py" | "pyi" => PYTHON_COMMENTS,
+        "sql" | "lua" | "hs" | "elm" | "ada" | "adb" | "ads" => DASH_COMMENTS,
+        "el" | "lisp" | "clj" | "cljs" | "scm" | "rkt" | "asm" | "s" | "ini" => SEMICOLON_COMMENTS,
+        "tex" | "sty" | "cls" | "erl" | "hrl" => PERCENT_COMMENTS,
+        "html" | "htm" | "xml" | "xhtml" | "svg" | "vue" => MARKUP_COMMENTS,
+        _ => match &path[path.rfind('/').map_or(0, |idx| idx + 1)..] {
+            "Makefile" | "GNUmakefile" | "makefile" | "Dockerfile" | "CMakeLists.txt" => {
+                HASH_COMMENTS
+            }
+            _ => &[],
+        },
+    }
+}
+
+/// Is `line` blank or a comment, given the comment `prefixes`
+fn is_comment(line: &Line, prefixes: &[&str]) -> bool {
+    let value = line.value.trim();
+    value.is_empty()
+        || prefixes
+            .iter()
+            .any(|p| value.starts_with(p) || value == p.trim_end())
+}
+
+impl PatchedFile {
+    /// Counts of the changes of this file
+    ///
+    /// Each run of removed and added lines is one change. A change is
+    /// whitespace only when its lines are the same once whitespace is
+    /// dropped, and comment only when all its lines are comments or blank.
+    /// Comments are recognized by the syntax of the language of the file,
+    /// told by its extension or name, so changes to files of an unknown
+    /// type are never comment only.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/f.c\n+++ b/f.c\n@@ -1,3 +1,3 @@\n-int a;\n+int  a;\n b;\n-// one\n+// two\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let stats = patch[0].change_stats();
+    /// assert_eq!((2, 2), (stats.whitespace_only, stats.comment_only));
+    /// ```
+    pub fn change_stats(&self) -> ChangeStats {
+        let prefixes = comment_prefixes(&self.path());
+        let mut stats = ChangeStats {
+            files: 1,
+            hunks: self.hunks.len(),
+            added: self.added(),
+            removed: self.removed(),
+            ..ChangeStats::default()
+        };
+        for hunk in &self.hunks {
+            let mut change: Vec<&Line> = vec![];
+            // a trailing context line ends the last change
+            for line in hunk.lines.iter().map(Some).chain(Some(None)) {
+                if let Some(line) = line.filter(|l| l.is_added() || l.is_removed()) {
+                    change.push(line);
+                    continue;
+                }
+                if line.is_some_and(|l| l.line_type == LineType::Empty) {
+                    continue;
+                }
+                if change.is_empty() {
+                    continue;
+                }
+                let (removed, added): (Vec<&Line>, Vec<&Line>) =
+                    change.iter().partition(|l| l.is_removed());
+                if without_whitespace(&removed) == without_whitespace(&added) {
+                    stats.whitespace_only += change.len();
+                } else if change.iter().all(|l| is_comment(l, prefixes)) {
+                    stats.comment_only += change.len();
+                }
+                change.clear();
+            }
+        }
+        stats
+    }
+}
+
+/// Directory of `path`, empty for files at the top
+fn directory(path: &str) -> String {
+    path.rfind('/').map_or("", |idx| &path[..idx]).to_owned()
+}
+
+/// Extension of the file name of `path`, empty when it has none
+fn extension(path: &str) -> String {
+    let name = &path[path.rfind('/').map_or(0, |idx| idx + 1)..];
+    match name.rfind('.') {
+        Some(idx) if idx > 0 => name[idx + 1..].to_owned(),
+        _ => String::new(),
+    }
+}
+
+/// Files of a directory during `dirstat`, sorted by path
+struct DirstatFiles<'a> {
+    files: &'a [(String, usize)],
+    changed: usize,
+    permille: usize,
+    cumulative: bool,
+    out: String,
+}
+
+impl<'a> DirstatFiles<'a> {
+    /// Changes of the files under `base`, printing the directories past the
+    /// threshold, as git's `gather_dirstat`
+    fn gather(&mut self, base: &str) -> usize {
+        let mut sum = 0;
+        let mut sources = 0;
+        while let Some((name, changes)) = self.files.first() {
+            if !name.starts_with(base) {
+                break;
+            }
+            match name[base.len()..].find('/') {
+                Some(slash) => {
+                    let sub = name[..base.len() + slash + 1].to_owned();
+                    sum += self.gather(&sub);
+                    sources += 1;
+                }
+                None => {
+                    sum += changes;
+                    self.files = &self.files[1..];
+                    sources += 2;
+                }
+            }
+        }
+        // the top and directories whose changes all come from a single
+        // subdirectory are not shown
+        if !base.is_empty() && sources != 1 && sum > 0 {
+            let permille = sum * 1000 / self.changed;
+            if permille >= self.permille {
+                self.out.push_str(&format!(
+                    "{:4}.{}% {}\n",
+                    permille / 10,
+                    permille % 10,
+                    base
+                ));
+                if !self.cumulative {
+                    return 0;
+                }
+            }
+        }
+        sum
+    }
+}
+
+impl PatchSet {
+    /// Counts of the changes of all the files
+    pub fn change_stats(&self) -> ChangeStats {
+        let mut stats = ChangeStats::default();
+        for file in &self.files {
+            stats += file.change_stats();
+        }
+        stats
+    }
+
+    /// Counts of the changes of the files grouped by `key`
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-a\n+b\n\
+    ///                        --- a/docs/a.md\n+++ b/docs/a.md\n@@ -1 +1,2 @@\n a\n+b\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// let stats = patch.stats_by(|f| f.path().ends_with(".rs"));
+    /// assert_eq!(2, stats[&true].churn());
+    /// assert_eq!(1, stats[&false].churn());
+    /// ```
+    pub fn stats_by<K, F>(&self, mut key: F) -> BTreeMap<K, ChangeStats>
+    where
+        K: Ord,
+        F: FnMut(&PatchedFile) -> K,
+    {
+        let mut groups: BTreeMap<K, ChangeStats> = BTreeMap::new();
+        for file in &self.files {
+            *groups.entry(key(file)).or_default() += file.change_stats();
+        }
+        groups
+    }
+
+    /// Counts of the changes of the files grouped by directory, the empty
+    /// string for files at the top
+    pub fn stats_by_directory(&self) -> BTreeMap<String, ChangeStats> {
+        self.stats_by(|f| directory(&f.path()))
+    }
+
+    /// Counts of the changes of the files grouped by extension, without
+    /// the dot, the empty string for files without one
+    pub fn stats_by_extension(&self) -> BTreeMap<String, ChangeStats> {
+        self.stats_by(|f| extension(&f.path()))
+    }
+
+    /// Share of the changed lines of each directory, like
+    /// `git diff --dirstat=lines`
+    ///
+    /// Directories below `min_percent` are left out and their changes count
+    /// towards their parent. When `cumulative`, the changes of a directory
+    /// shown also count towards its parent.
+    ///
+    /// ```
+    /// use unidiff::PatchSet;
+    ///
+    /// let patch: PatchSet = "--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-a\n+b\n\
+    ///                        --- a/docs/a.md\n+++ b/docs/a.md\n@@ -1 +1,2 @@\n a\n+b\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!("  33.3% docs/\n  66.6% src/\n", patch.dirstat(3.0, false));
+    /// ```
+    pub fn dirstat(&self, min_percent: f64, cumulative: bool) -> String {
+        let mut files: Vec<(String, usize)> = self
+            .files
+            .iter()
+            .map(|f| (f.path(), f.added() + f.removed()))
+            .filter(|&(_, changes)| changes > 0)
+            .collect();
+        files.sort();
+        let changed = files.iter().map(|&(_, changes)| changes).sum();
+        if changed == 0 {
+            return String::new();
+        }
+        let mut dirstat = DirstatFiles {
+            files: &files,
+            changed,
+            permille: (min_percent * 10.0) as usize,
+            cumulative,
+            out: String::new(),
+        };
+        dirstat.gather("");
+        dirstat.out
+    }
+}
@@ -0,0 +1,187 @@
+//! Subversion specific parts of a diff
+
+use std::fmt;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{Hunk, Result};
+
+lazy_static! {
+    static ref RE_REVISION: Regex = Regex::new(r"^\((?:revision (?P<number>\d+)|(?P<working>working copy)|(?P<nonexistent>nonexistent))\)$").unwrap();
+    pub(crate) static ref RE_PROPERTY_CHANGES: Regex = Regex::new(r"^Property changes on: (?P<path>.+)$").unwrap();
+    static ref RE_PROPERTY: Regex = Regex::new(r"^(?P<action>Added|Modified|Deleted): (?P<name>.+)$").unwrap();
+    static ref RE_PROPERTY_HUNK_HEADER: Regex = Regex::new(r"^## -(?P<source_start>\d+)(?:,(?P<source_length>\d+))? \+(?P<target_start>\d+)(?:,(?P<target_length>\d+))? ##").unwrap();
+}
+
+/// Underline written below `Property changes on:`
+const PROPERTY_UNDERLINE: &str =
+    "___________________________________________________________________";
+
+/// Revision annotation svn writes after a file name, e.g. `(revision 191)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SvnRevision {
+    /// `(revision N)`
+    Number(u64),
+    /// `(working copy)`
+    WorkingCopy,
+    /// `(nonexistent)`
+    Nonexistent,
+}
+
+impl SvnRevision {
+    /// Parse an annotation such as `(revision 191)`
+    pub fn parse(annotation: &str) -> Option<SvnRevision> {
+        let captures = RE_REVISION.captures(annotation.trim())?;
+        if let Some(number) = captures.name("number") {
+            return number.as_str().parse().ok().map(SvnRevision::Number);
+        }
+        if captures.name("working").is_some() {
+            return Some(SvnRevision::WorkingCopy);
+        }
+        Some(SvnRevision::Nonexistent)
+    }
+}
+
+impl fmt::Display for SvnRevision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SvnRevision::Number(n) => write!(f, "(revision {})", n),
+            SvnRevision::WorkingCopy => write!(f, "(working copy)"),
+            SvnRevision::Nonexistent => write!(f, "(nonexistent)"),
+        }
+    }
+}
+
+/// How a property changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropertyAction {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl fmt::Display for PropertyAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PropertyAction::Added => write!(f, "Added"),
+            PropertyAction::Modified => write!(f, "Modified"),
+            PropertyAction::Deleted => write!(f, "Deleted"),
+        }
+    }
+}
+
+/// A property change from a `Property changes on:` block
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropertyChange {
+    /// How the property changed
+    pub action: PropertyAction,
+    /// Property name, e.g. `svn:eol-style`
+    pub name: String,
+    /// Changes to the property value, in `## -0,0 +1 ##` hunks
+    pub hunks: Vec<Hunk>,
+}
+
+impl PropertyChange {
+    /// Property value before the change, `None` if it was added
+    pub fn old_value(&self) -> Option<String> {
+        if self.action == PropertyAction::Added {
+            return None;
+        }
+        Some(self.value(Hunk::source_lines))
+    }
+
+    /// Property value after the change, `None` if it was deleted
+    pub fn new_value(&self) -> Option<String> {
+        if self.action == PropertyAction::Deleted {
+            return None;
+        }
+        Some(self.value(Hunk::target_lines))
+    }
+
+    fn value(&self, lines: fn(&Hunk) -> Vec<crate::Line>) -> String {
+        self.hunks
+            .iter()
+            .flat_map(lines)
+            .map(|l| l.value)
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl fmt::Display for PropertyChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.action, self.name)?;
+        for hunk in &self.hunks {
+            write!(
+                f,
+                "\n## -{} +{} ##",
+                range(hunk.source_start, hunk.source_length),
+                range(hunk.target_start, hunk.target_length)
+            )?;
+            for line in hunk.lines() {
+                write!(f, "\n{}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn range(start: usize, length: usize) -> String {
+    if length == 1 {
+        start.to_string()
+    } else {
+        format!("{},{}", start, length)
+    }
+}
+
+/// Write a `Property changes on:` block, without a trailing newline
+pub(crate) fn fmt_properties(
+    f: &mut fmt::Formatter,
+    path: &str,
+    properties: &[PropertyChange],
+) -> fmt::Result {
+    write!(f, "Property changes on: {}\n{}", path, PROPERTY_UNDERLINE)?;
+    for property in properties {
+        write!(f, "\n{}", property)?;
+    }
+    Ok(())
+}
+
+/// Parse the property changes following a `Property changes on:` line,
+/// returning them and the count of lines they span
+pub(crate) fn parse_properties(diff: &[(usize, &str)]) -> Result<(Vec<PropertyChange>, usize)> {
+    let mut properties = vec![];
+    let mut idx = 0;
+    if diff.first().is_some_and(|&(_, l)| l.starts_with("___")) {
+        idx += 1;
+    }
+    while idx < diff.len() {
+        let captures = match RE_PROPERTY.captures(diff[idx].1) {
+            Some(captures) => captures,
+            None => break,
+        };
+        let action = match &captures["action"] {
+            "Added" => PropertyAction::Added,
+            "Deleted" => PropertyAction::Deleted,
+            _ => PropertyAction::Modified,
+        };
+        let mut property = PropertyChange {
+            action,
+            name: captures["name"].to_owned(),
+            hunks: vec![],
+        };
+        idx += 1;
+        while idx < diff.len() {
+            let header_info = match RE_PROPERTY_HUNK_HEADER.captures(diff[idx].1) {
+                Some(header_info) => header_info,
+                None => break,
+            };
+            let (hunk, consumed) = Hunk::parse(&header_info, &diff[idx + 1..])?;
+            property.hunks.push(hunk);
+            idx += 1 + consumed;
+        }
+        properties.push(property);
+    }
+    Ok((properties, idx))
+}
@@ -0,0 +1,270 @@
+//! Parsing `git diff --word-diff` output
+
+use std::collections::HashMap;
+
+use crate::{LineType, PatchSet, Result, RE_HUNK_HEADER};
+
+/// Flavour of `git diff --word-diff` output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WordDiffFormat {
+    /// `--word-diff=plain`: changes inline as `[-old-]` and `{+new+}`
+    Plain,
+    /// `--word-diff=porcelain`: one word per line prefixed by ` `, `-` or
+    /// `+`, lines ended by `~`
+    Porcelain,
+}
+
+/// A run of words of a line and how it changed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WordSegment {
+    /// `Context` for words on both sides, `Removed` or `Added` otherwise
+    pub line_type: LineType,
+    /// Words of the run, with the whitespace between them
+    pub value: String,
+}
+
+impl WordSegment {
+    fn new<T: Into<String>>(value: T, line_type: LineType) -> WordSegment {
+        WordSegment {
+            line_type,
+            value: value.into(),
+        }
+    }
+}
+
+/// Segments of a `--word-diff=plain` line
+fn parse_plain(line: &str) -> Vec<WordSegment> {
+    let mut segments = vec![];
+    let mut rest = line;
+    loop {
+        let next = [
+            ("[-", "-]", LineType::Removed),
+            ("{+", "+}", LineType::Added),
+        ]
+        .iter()
+        .filter_map(|(open, close, line_type)| {
+            let start = rest.find(open)?;
+            let end = rest[start + 2..].find(close)? + start + 2;
+            Some((start, end, line_type.clone()))
+        })
+        .min_by_key(|&(start, ..)| start);
+        match next {
+            Some((start, end, line_type)) => {
+                if start > 0 {
+                    segments.push(WordSegment::new(&rest[..start], LineType::Context));
+                }
+                segments.push(WordSegment::new(&rest[start + 2..end], line_type));
+                rest = &rest[end + 2..];
+            }
+            None => {
+                if !rest.is_empty() {
+                    segments.push(WordSegment::new(rest, LineType::Context));
+                }
+                return segments;
+            }
+        }
+    }
+}
+
+/// Segments of `line_type` and context, merged where they follow each other
+fn side(segments: &[WordSegment], line_type: &LineType) -> Vec<WordSegment> {
+    let mut side: Vec<WordSegment> = vec![];
+    for segment in segments {
+        if segment.line_type != LineType::Context && segment.line_type != *line_type {
+            continue;
+        }
+        match side.last_mut() {
+            Some(last) if last.line_type == segment.line_type => {
+                last.value.push_str(&segment.value)
+            }
+            _ => side.push(segment.clone()),
+        }
+    }
+    side
+}
+
+fn text(segments: &[WordSegment]) -> String {
+    segments.iter().map(|s| s.value.as_str()).collect()
+}
+
+/// A hunk being turned into unified diff lines, with the lengths its header
+/// declares and those of the lines converted so far
+struct PendingHunk {
+    header_idx: usize,
+    declared: (usize, usize),
+    source_length: usize,
+    target_length: usize,
+}
+
+impl PendingHunk {
+    /// Have all the lines the header declares been converted
+    fn is_used_up(&self) -> bool {
+        self.source_length >= self.declared.0 && self.target_length >= self.declared.1
+    }
+}
+
+/// Word diff turned into a unified diff, with the segments of each line
+#[derive(Default)]
+struct Converter {
+    lines: Vec<(usize, String)>,
+    segments: HashMap<(usize, bool), Vec<WordSegment>>,
+    hunk: Option<PendingHunk>,
+}
+
+impl Converter {
+    /// Add the lines for the line of the word diff at `line_no` made of
+    /// `segments`
+    fn push(&mut self, line_no: usize, segments: Vec<WordSegment>) {
+        let has = |line_type: LineType| segments.iter().any(|s| s.line_type == line_type);
+        let (removed, added) = (has(LineType::Removed), has(LineType::Added));
+        let unchanged_text = segments
+            .iter()
+            .any(|s| s.line_type == LineType::Context && !s.value.trim().is_empty());
+        let (lines, all_segments) = (&mut self.lines, &mut self.segments);
+        let hunk = self.hunk.as_mut().unwrap();
+        let mut add_line = |prefix: char, line_type: LineType, is_added: bool| {
+            let segments = side(&segments, &line_type);
+            lines.push((line_no, format!("{}{}", prefix, text(&segments))));
+            all_segments.insert((line_no, is_added), segments);
+        };
+        if !removed && !added {
+            add_line(' ', LineType::Context, false);
+            hunk.source_length += 1;
+            hunk.target_length += 1;
+            return;
+        }
+        if removed || unchanged_text {
+            add_line('-', LineType::Removed, false);
+            hunk.source_length += 1;
+        }
+        if added || unchanged_text {
+            add_line('+', LineType::Added, true);
+            hunk.target_length += 1;
+        }
+    }
+
+    /// Write the lengths of the hunk being converted into its header
+    fn finish_hunk(&mut self) {
+        if let Some(hunk) = self.hunk.take() {
+            let (line_no, header) = &self.lines[hunk.header_idx];
+            let captures = RE_HUNK_HEADER.captures(header).unwrap();
+            let header = format!(
+                "@@ -{},{} +{},{} @@ {}",
+                &captures["source_start"],
+                hunk.source_length,
+                &captures["target_start"],
+                hunk.target_length,
+                &captures["section_header"]
+            );
+            self.lines[hunk.header_idx] = (*line_no, header);
+        }
+    }
+
+    fn convert(&mut self, input: &str, format: WordDiffFormat) {
+        let mut porcelain = vec![];
+        let mut porcelain_start = 0;
+        for (line_no, line) in input.lines().enumerate() {
+            // a "no newline" marker may follow the last line of a hunk
+            if self.hunk.is_some() && line.starts_with("\\ No newline at end of") {
+                self.lines.push((line_no, line.to_owned()));
+                continue;
+            }
+            if self.hunk.as_ref().is_some_and(PendingHunk::is_used_up) {
+                self.finish_hunk();
+            }
+            if self.hunk.is_none() {
+                if let Some(captures) = RE_HUNK_HEADER.captures(line) {
+                    let length = |name| {
+                        captures
+                            .name(name)
+                            .map_or(1, |m| m.as_str().parse().unwrap_or(0))
+                    };
+                    self.hunk = Some(PendingHunk {
+                        header_idx: self.lines.len(),
+                        declared: (length("source_length"), length("target_length")),
+                        source_length: 0,
+                        target_length: 0,
+                    });
+                }
+                self.lines.push((line_no, line.to_owned()));
+                continue;
+            }
+            match format {
+                WordDiffFormat::Plain => self.push(line_no, parse_plain(line)),
+                WordDiffFormat::Porcelain => {
+                    if porcelain.is_empty() {
+                        porcelain_start = line_no;
+                    }
+                    let line_type = match line.chars().next() {
+                        Some('-') => LineType::Removed,
+                        Some('+') => LineType::Added,
+                        Some(' ') => LineType::Context,
+                        Some('~') => {
+                            self.push(porcelain_start, porcelain.split_off(0));
+                            continue;
+                        }
+                        // not a word diff line: the hunk ended early
+                        _ => {
+                            self.finish_hunk();
+                            self.lines.push((line_no, line.to_owned()));
+                            continue;
+                        }
+                    };
+                    porcelain.push(WordSegment::new(&line[1..], line_type));
+                }
+            }
+        }
+        self.finish_hunk();
+    }
+}
+
+impl PatchSet {
+    /// Parse the output of `git diff --word-diff`
+    ///
+    /// Lines with changed words become a removed line and an added line,
+    /// each with the segments of its side, so the patch set can be used as
+    /// any other; unchanged lines are context lines.
+    ///
+    /// ```
+    /// use unidiff::{LineType, PatchSet, WordDiffFormat};
+    ///
+    /// let mut patch = PatchSet::new();
+    /// patch
+    ///     .parse_word_diff(
+    ///         "--- a/doc.md\n+++ b/doc.md\n@@ -1,2 +1,2 @@\n# Title\nSome [-old-]{+new+} text\n",
+    ///         WordDiffFormat::Plain,
+    ///     )
+    ///     .unwrap();
+    /// let hunk = &patch[0][0];
+    /// assert_eq!("Some old text", hunk[1].value);
+    /// assert_eq!("Some new text", hunk[2].value);
+    /// assert_eq!(LineType::Added, hunk[2].segments[1].line_type);
+    /// assert_eq!("new", hunk[2].segments[1].value);
+    /// ```
+    pub fn parse_word_diff<T: AsRef<str>>(
+        &mut self,
+        input: T,
+        format: WordDiffFormat,
+    ) -> Result<()> {
+        let mut converter = Converter::default();
+        converter.convert(input.as_ref(), format);
+        let lines: Vec<(usize, &str)> = converter
+            .lines
+            .iter()
+            .map(|(line_no, line)| (*line_no, line.as_str()))
+            .collect();
+        let first = self.files.len();
+        self.parse_lines(&lines)?;
+        for file in &mut self.files[first..] {
+            for hunk in &mut file.hunks {
+                for line in &mut hunk.lines {
+                    let key = (line.diff_line_no - 1, line.is_added());
+                    if let Some(segments) = converter.segments.get(&key) {
+                        line.segments = segments.clone();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+extern crate unidiff;
+
+use unidiff::PatchSet;
+
+const FIRST: &str = "--- a/numbers
++++ b/numbers
+@@ -1,3 +1,4 @@
+ 1
+-2
++two
++2.5
+ 3
+";
+
+#[test]
+fn test_combine_separate_hunks() {
+    let first: PatchSet = FIRST.parse().unwrap();
+    let second: PatchSet = "--- a/numbers
++++ b/numbers
+@@ -9,3 +9,3 @@
+ 8
+-9
++nine
+ 10
+"
+    .parse()
+    .unwrap();
+
+    let combined = first.combine(&second).unwrap();
+    assert_eq!(1, combined.len());
+    let file = &combined[0];
+    assert_eq!(2, file.len());
+    assert_eq!(first[0][0], file[0]);
+    assert_eq!(
+        (8, 3, 9, 3),
+        (
+            file[1].source_start,
+            file[1].source_length,
+            file[1].target_start,
+            file[1].target_length
+        )
+    );
+    assert_eq!(Some(9), file[1][1].source_line_no);
+    assert_eq!(Some(10), file[1][2].target_line_no);
+}
+
+#[test]
+fn test_combine_overlapping_hunks() {
+    let first: PatchSet = FIRST.parse().unwrap();
+    let second: PatchSet = "--- a/numbers
++++ b/numbers
+@@ -2,3 +2,2 @@
+ two
+-2.5
+-3
++three
+"
+    .parse()
+    .unwrap();
+
+    let combined = first.combine(&second).unwrap();
+    assert_eq!(
+        "--- a/numbers\n+++ b/numbers\n@@ -1,3 +1,3 @@ \n 1\n-2\n-3\n+two\n+three",
+        combined.to_string()
+    );
+}
+
+#[test]
+fn test_combine_drops_reverted_files() {
+    let first: PatchSet = "--- a/numbers
++++ b/numbers
+@@ -2 +2 @@
+-2
++two
+--- /dev/null
++++ b/new
+@@ -0,0 +1 @@
++new
+--- a/other
++++ b/other
+@@ -1 +1 @@
+-a
++b
+"
+    .parse()
+    .unwrap();
+    let second: PatchSet = "--- a/numbers
++++ b/numbers
+@@ -2 +2 @@
+-two
++2
+--- a/new
++++ /dev/null
+@@ -1 +0,0 @@
+-new
+--- a/last
++++ b/last
+@@ -1 +1 @@
+-y
++z
+"
+    .parse()
+    .unwrap();
+
+    let combined = first.combine(&second).unwrap();
+    let paths: Vec<String> = combined.files().iter().map(|f| f.path()).collect();
+    assert_eq!(vec!["other", "last"], paths);
+}
+
+#[test]
+fn test_combine_mismatch() {
+    let first: PatchSet = FIRST.parse().unwrap();
+    let second: PatchSet = "--- a/numbers
++++ b/numbers
+@@ -2 +2 @@
+-2
++TWO
+"
+    .parse()
+    .unwrap();
+    assert!(first.combine(&second).is_err());
+}
+
+const MODE_AND_BINARY: &str = "diff --git a/script.sh b/script.sh
+old mode 100644
+new mode 100755
+diff --git a/image.png b/image.png
+index 1111111..2222222 100644
+Binary files a/image.png and b/image.png differ
+diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -1 +1 @@
+-a
++b
+";
+
+#[test]
+fn test_combine_keeps_header_only_files() {
+    let first: PatchSet = MODE_AND_BINARY.parse().unwrap();
+    let second: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-b\n+c\n".parse().unwrap();
+
+    let combined = first.combine(&second).unwrap();
+    let paths: Vec<String> = combined.files().iter().map(|f| f.path()).collect();
+    assert_eq!(vec!["script.sh", "image.png", "f"], paths);
+    assert_eq!(first[0], combined[0]);
+    assert_eq!(first[1], combined[1]);
+
+    // changing the binary file again or reverting the mode change
+    let second: PatchSet = "diff --git a/script.sh b/script.sh
+old mode 100755
+new mode 100644
+diff --git a/image.png b/image.png
+index 2222222..3333333 100644
+Binary files a/image.png and b/image.png differ
+"
+    .parse()
+    .unwrap();
+    let combined = first.combine(&second).unwrap();
+    let paths: Vec<String> = combined.files().iter().map(|f| f.path()).collect();
+    assert_eq!(vec!["image.png", "f"], paths);
+    assert!(combined[0].is_binary());
+}
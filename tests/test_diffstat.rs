@@ -0,0 +1,68 @@
+extern crate unidiff;
+
+use unidiff::PatchSet;
+
+const HEADER_ONLY: &str = include_str!("fixtures/git_header_only.diff");
+
+#[test]
+fn test_stat_render() {
+    let patch: PatchSet = HEADER_ONLY.parse().unwrap();
+    let stat = patch.stat();
+    assert_eq!(
+        " logo.png                | Bin
+ src/{old => new}/lib.rs |   0
+ run.sh                  |   0
+ README                  |   3 ++-
+ 4 files changed, 2 insertions(+), 1 deletion(-)
+",
+        stat.to_string()
+    );
+    assert_eq!(
+        "-\t-\tlogo.png
+0\t0\tsrc/{old => new}/lib.rs
+0\t0\trun.sh
+2\t1\tREADME
+",
+        stat.numstat()
+    );
+}
+
+#[test]
+fn test_stat_scales_graph() {
+    let mut buf = String::from("--- a/big\n+++ b/big\n@@ -0,0 +1,200 @@\n");
+    for idx in 0..200 {
+        buf.push_str(&format!("+{}\n", idx));
+    }
+    let patch: PatchSet = buf.parse().unwrap();
+    assert_eq!(
+        format!(
+            " big | 200 {}\n 1 file changed, 200 insertions(+)\n",
+            "+".repeat(18)
+        ),
+        patch.stat().render(30)
+    );
+}
+
+#[test]
+fn test_shortstat() {
+    let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1,2 +0,0 @@\n-a\n-b\n"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        " 1 file changed, 2 deletions(-)\n",
+        patch.stat().shortstat()
+    );
+    assert_eq!(" 0 files changed\n", PatchSet::new().stat().shortstat());
+}
+
+#[test]
+fn test_summary() {
+    let patch: PatchSet = HEADER_ONLY.parse().unwrap();
+    assert_eq!(
+        " create mode 100644 logo.png
+ rename src/{old => new}/lib.rs (100%)
+ mode change 100644 => 100755 run.sh
+",
+        patch.summary()
+    );
+}
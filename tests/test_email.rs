@@ -0,0 +1,194 @@
+extern crate unidiff;
+
+use unidiff::{format_series, PatchEmail, PatchSet};
+
+#[test]
+fn test_format_patch_email() {
+    let buf = "--- a/sample.txt
++++ b/sample.txt
+@@ -1,2 +1,1 @@
+ hello
+-world
+";
+    let patch: PatchSet = buf.parse().unwrap();
+
+    let mut email = PatchEmail::new(
+        "A U Thor <author@example.com>",
+        "Thu, 7 Apr 2005 15:13:13 -0700",
+        "Remove greeting",
+        patch,
+    );
+    email.commit = Some("2fa9a2ab3f1d5b0c0b1a2c3d4e5f60718293a4b5".to_owned());
+    email.message = "Nobody reads it anyway.\n".to_owned();
+    email.signature = "2.39.2".to_owned();
+
+    let expected = "From 2fa9a2ab3f1d5b0c0b1a2c3d4e5f60718293a4b5 Mon Sep 17 00:00:00 2001
+From: A U Thor <author@example.com>
+Date: Thu, 7 Apr 2005 15:13:13 -0700
+Subject: [PATCH] Remove greeting
+
+Nobody reads it anyway.
+---
+ sample.txt | 1 -
+ 1 file changed, 1 deletion(-)
+
+diff --git a/sample.txt b/sample.txt
+--- a/sample.txt
++++ b/sample.txt
+@@ -1,2 +1,1 @@ 
+ hello
+-world
+-- 
+2.39.2
+
+";
+    assert_eq!(expected, email.to_string());
+}
+
+#[test]
+fn test_format_patch_diffstat() {
+    let buf = include_str!("fixtures/git.diff");
+    let patch: PatchSet = buf.parse().unwrap();
+
+    let email = PatchEmail::new(
+        "A <a@example.com>",
+        "Thu, 1 Jan 1970 00:00:00 +0000",
+        "Files",
+        patch,
+    );
+    let rendered = email.to_string();
+    assert!(rendered.contains(
+        "---
+ added_file    | 4 ++++
+ modified_file | 4 +++-
+ removed_file  | 3 ---
+ 3 files changed, 7 insertions(+), 4 deletions(-)
+ create mode 100644 added_file
+ delete mode 100644 removed_file
+
+diff --git a/added_file b/added_file
+"
+    ));
+}
+
+#[test]
+fn test_format_series_numbering() {
+    let patch: PatchSet = include_str!("fixtures/sample4.diff").parse().unwrap();
+    let mut emails: Vec<PatchEmail> = (0..10)
+        .map(|i| {
+            PatchEmail::new(
+                "A <a@example.com>".to_owned(),
+                "Thu, 1 Jan 1970 00:00:00 +0000".to_owned(),
+                format!("Change {}", i),
+                patch.clone(),
+            )
+        })
+        .collect();
+
+    let rendered = format_series(&mut emails);
+    assert_eq!(10, rendered.len());
+    assert!(rendered[0].contains("Subject: [PATCH 01/10] Change 0\n"));
+    assert!(rendered[9].contains("Subject: [PATCH 10/10] Change 9\n"));
+    assert_eq!("[PATCH 03/10]", emails[2].subject_prefix());
+}
+
+#[test]
+fn test_format_patch_non_git_diff() {
+    let patch: PatchSet = include_str!("fixtures/svn.diff").parse().unwrap();
+
+    let email = PatchEmail::new(
+        "A <a@example.com>",
+        "Thu, 1 Jan 1970 00:00:00 +0000",
+        "Files",
+        patch,
+    );
+    let rendered = email.to_string();
+    assert!(rendered.contains(
+        "
+diff --git a/modified_file b/modified_file
+--- a/modified_file
++++ b/modified_file
+@@ -1,5 +1,7 @@ 
+"
+    ));
+    assert!(rendered.contains(
+        "
+diff --git a/removed_file b/removed_file
+deleted file mode 100644
+--- a/removed_file
++++ /dev/null
+@@ -1,3 +0,0 @@ 
+"
+    ));
+    assert!(rendered.contains(
+        "
+diff --git a/added_file b/added_file
+new file mode 100644
+--- /dev/null
++++ b/added_file
+@@ -0,0 +1,4 @@ 
+"
+    ));
+    assert!(!rendered.contains("Index: "));
+}
+
+#[test]
+fn test_format_patch_non_git_rename() {
+    let patch: PatchSet = "--- a/old.txt\n+++ b/new.txt\n@@ -1 +1 @@\n-a\n+b\n"
+        .parse()
+        .unwrap();
+
+    let email = PatchEmail::new(
+        "A <a@example.com>",
+        "Thu, 1 Jan 1970 00:00:00 +0000",
+        "Rename",
+        patch,
+    );
+    assert!(email.to_string().contains(
+        "
+diff --git a/old.txt b/new.txt
+rename from old.txt
+rename to new.txt
+--- a/old.txt
++++ b/new.txt
+"
+    ));
+}
+
+#[test]
+fn test_format_patch_non_ascii_headers() {
+    let patch: PatchSet = include_str!("fixtures/sample4.diff").parse().unwrap();
+
+    let email = PatchEmail::new(
+        "René Scharfe <rene@example.com>",
+        "Thu, 1 Jan 1970 00:00:00 +0000",
+        "Café au lait",
+        patch.clone(),
+    );
+    assert!(email.to_string().contains(
+        "From: =?UTF-8?q?Ren=C3=A9=20Scharfe?= <rene@example.com>
+Date: Thu, 1 Jan 1970 00:00:00 +0000
+Subject: [PATCH] =?UTF-8?q?Caf=C3=A9=20au=20lait?=
+MIME-Version: 1.0
+Content-Type: text/plain; charset=UTF-8
+Content-Transfer-Encoding: 8bit
+
+"
+    ));
+
+    // long subjects are folded into several encoded words, as git does
+    let email = PatchEmail::new(
+        "A <a@example.com>",
+        "Thu, 1 Jan 1970 00:00:00 +0000",
+        &"é".repeat(30),
+        patch,
+    );
+    let e = "=C3=A9";
+    assert!(email.to_string().contains(&format!(
+        "Subject: [PATCH] =?UTF-8?q?{}?=\n =?UTF-8?q?{}?=\n =?UTF-8?q?{}?=\n =?UTF-8?q?{}?=\n",
+        e.repeat(7),
+        e.repeat(10),
+        e.repeat(10),
+        e.repeat(3)
+    )));
+}
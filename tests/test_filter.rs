@@ -0,0 +1,78 @@
+extern crate unidiff;
+
+use unidiff::{Glob, PatchSet};
+
+#[test]
+fn test_glob_matching() {
+    let glob = Glob::new("src/**/*.rs");
+    assert!(glob.is_match("src/lib.rs"));
+    assert!(glob.is_match("src/a/b/c.rs"));
+    assert!(!glob.is_match("src/lib.rsx"));
+    assert!(!glob.is_match("tests/src/lib.rs"));
+
+    let glob = Glob::new("*.c?p");
+    assert!(glob.is_match("nsContentUtils.cpp"));
+    assert!(glob.is_match("content/base/src/nsContentUtils.cpp"));
+    assert!(!glob.is_match("a.cc"));
+
+    let glob = Glob::new("/vendor/*");
+    assert!(glob.is_match("vendor/dep.rs"));
+    assert!(!glob.is_match("vendor/dep/lib.rs"));
+    assert!(!glob.is_match("src/vendor/dep.rs"));
+
+    let glob = Glob::new("file[0-9][!a].txt");
+    assert!(glob.is_match("file1b.txt"));
+    assert!(!glob.is_match("file1a.txt"));
+    assert!(!glob.is_match("filex1.txt"));
+
+    let glob = Glob::new("[]a].txt");
+    assert!(glob.is_match("].txt"));
+    assert!(glob.is_match("a.txt"));
+    assert!(!glob.is_match("b.txt"));
+
+    let glob = Glob::new("[!]]x");
+    assert!(glob.is_match("ax"));
+    assert!(!glob.is_match("]x"));
+
+    let glob = Glob::new("[ab");
+    assert!(glob.is_match("[ab"));
+}
+
+#[test]
+fn test_include_exclude() {
+    let buf = include_str!("fixtures/sample2.diff");
+    let patch: PatchSet = buf.parse().unwrap();
+
+    let js = patch.include(["js/**"]);
+    assert_eq!(1, js.len());
+    assert_eq!("js/src/jsfriendapi.cpp", js[0].path());
+    // the preamble is kept
+    assert_eq!(patch.preamble(), js.preamble());
+
+    let content = patch.include(["*.cpp", "!js/**"]);
+    assert_eq!(1, content.len());
+    assert_eq!("content/base/src/nsContentUtils.cpp", content[0].path());
+
+    let content = patch.exclude(["js/**"]);
+    assert_eq!(1, content.len());
+    assert_eq!("content/base/src/nsContentUtils.cpp", content[0].path());
+
+    assert!(patch.include(["*.h"]).is_empty());
+    assert_eq!(2, patch.include(Vec::<&str>::new()).len());
+}
+
+#[test]
+fn test_filter_predicate() {
+    let buf = include_str!("fixtures/git.diff");
+    let patch: PatchSet = buf.parse().unwrap();
+
+    let removals = patch.filter(|f| f.removed() > 0);
+    assert_eq!(2, removals.len());
+    assert_eq!("modified_file", removals[0].path());
+    assert_eq!("removed_file", removals[1].path());
+
+    // deleted files match on their source path
+    assert_eq!(1, patch.include(["removed_*"]).len());
+    // added files match on their target path
+    assert_eq!(1, patch.include(["added_*"]).len());
+}
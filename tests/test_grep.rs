@@ -0,0 +1,76 @@
+extern crate regex;
+extern crate unidiff;
+
+use regex::Regex;
+use unidiff::{LineType, PatchSet};
+
+#[test]
+fn test_grep_lines() {
+    let buf = include_str!("fixtures/sample0.diff");
+    let patch: PatchSet = buf.parse().unwrap();
+
+    let changed = [LineType::Added, LineType::Removed];
+    let found = patch.grep(&Regex::new("document").unwrap(), &changed);
+    let positions: Vec<(usize, usize, usize)> = found
+        .iter()
+        .map(|m| (m.file_index, m.hunk_index, m.line_index))
+        .collect();
+    assert_eq!(
+        vec![
+            (0, 0, 4),
+            (0, 1, 14),
+            (0, 2, 6),
+            (1, 0, 4),
+            (1, 0, 7),
+            (2, 0, 4),
+            (2, 0, 7)
+        ],
+        positions
+    );
+    assert_eq!("/path/to/original", found[0].path);
+    assert_eq!(Some(5), found[0].line.target_line_no);
+    assert_eq!(None, found[0].line.source_line_no);
+    assert_eq!(Some(17), found[1].line.target_line_no);
+    assert_eq!(28, found[1].line.diff_line_no);
+
+    // context lines are only searched when asked for
+    let context = patch.grep(&Regex::new("^document has").unwrap(), &[LineType::Context]);
+    assert_eq!(1, context.len());
+    assert!(context[0].line.is_context());
+}
+
+#[test]
+fn test_grep_hunks() {
+    let buf = include_str!("fixtures/sample0.diff");
+    let patch: PatchSet = buf.parse().unwrap();
+
+    let reduced = patch.grep_hunks(&Regex::new("dokument").unwrap(), &[LineType::Removed]);
+    assert_eq!(1, reduced.len());
+    assert_eq!(1, reduced[0].len());
+    assert_eq!(5, reduced[0][0].source_start);
+    assert_eq!(8, reduced[0][0].removed());
+
+    let files = patch.grep_files(&Regex::new("important").unwrap(), &[LineType::Added]);
+    assert_eq!(2, files.len());
+    assert_eq!("/path/to/original", files[0].path());
+    assert_eq!("/path/to/another_new", files[1].path());
+    assert_eq!(3, files[0].len());
+
+    assert!(patch
+        .grep_hunks(
+            &Regex::new("nothing like this").unwrap(),
+            &[LineType::Added]
+        )
+        .is_empty());
+}
+
+#[test]
+fn test_find_lines() {
+    let buf = include_str!("fixtures/git.diff");
+    let patch: PatchSet = buf.parse().unwrap();
+
+    let empty_added = patch.find_lines(|l| l.is_added() && l.value.is_empty());
+    assert_eq!(2, empty_added.len());
+    assert_eq!("added_file", empty_added[0].path);
+    assert_eq!("modified_file", empty_added[1].path);
+}
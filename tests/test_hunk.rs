@@ -0,0 +1,242 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+extern crate unidiff;
+
+use unidiff::{Granularity, Hunk, Line, LineType, PatchSet, PatchedFile};
+
+const TWO_CHANGES: &str = "--- a/file
++++ b/file
+@@ -1,7 +1,8 @@ fn main()
+ one
+-two
++TWO
+ three
+ four
+ five
++five and a half
+-six
++SIX
+ seven
+";
+
+fn line_numbers(hunk: &Hunk) -> Vec<(Option<usize>, Option<usize>)> {
+    hunk.lines()
+        .iter()
+        .map(|l| (l.source_line_no, l.target_line_no))
+        .collect()
+}
+
+#[test]
+fn test_split_hunk() {
+    let patch: PatchSet = TWO_CHANGES.parse().unwrap();
+    let hunk = &patch[0][0];
+
+    let split = hunk.split();
+    assert_eq!(2, split.len());
+
+    assert_eq!(
+        (1, 5, 1, 5),
+        (
+            split[0].source_start,
+            split[0].source_length,
+            split[0].target_start,
+            split[0].target_length
+        )
+    );
+    assert_eq!(1, split[0].added());
+    assert_eq!(1, split[0].removed());
+    assert_eq!("fn main()", split[0].section_header);
+
+    // the context between the changes is in both hunks
+    assert_eq!(
+        (3, 5, 3, 6),
+        (
+            split[1].source_start,
+            split[1].source_length,
+            split[1].target_start,
+            split[1].target_length
+        )
+    );
+    assert_eq!(" three", split[1][0].to_string());
+    assert_eq!(2, split[1].added());
+    assert_eq!(1, split[1].removed());
+    assert_eq!(line_numbers(hunk)[3..], line_numbers(&split[1])[..]);
+}
+
+#[test]
+fn test_split_single_change() {
+    let patch: PatchSet = include_str!("fixtures/sample1.diff").parse().unwrap();
+    let hunk = &patch[0][0];
+    assert_eq!(vec![hunk.clone()], hunk.split());
+}
+
+#[test]
+fn test_merge_adjacent_hunks() {
+    let patch: PatchSet = TWO_CHANGES.parse().unwrap();
+    let original = patch[0][0].clone();
+
+    let mut file = PatchedFile::with_hunks("a/file", "b/file", original.split());
+    assert_eq!(2, file.len());
+
+    file.merge_adjacent_hunks();
+    assert_eq!(1, file.len());
+    assert_eq!(original, file[0]);
+}
+
+#[test]
+fn test_merge_keeps_distant_hunks() {
+    let patch: PatchSet = include_str!("fixtures/sample0.diff").parse().unwrap();
+    let mut file = patch[0].clone();
+    file.merge_adjacent_hunks();
+    assert_eq!(patch[0], file);
+}
+
+#[test]
+fn test_merge_contained_hunk() {
+    let diff = "--- a/file
++++ b/file
+@@ -1,5 +1,5 @@
+-a
++A
+ b
+ c
+ d
+ e
+@@ -3,1 +3,1 @@
+ c
+";
+    let patch: PatchSet = diff.parse().unwrap();
+    let mut file = patch[0].clone();
+    file.merge_adjacent_hunks();
+    assert_eq!(1, file.len());
+    assert_eq!(patch[0][0], file[0]);
+}
+
+#[test]
+fn test_select_lines() {
+    let patch: PatchSet = TWO_CHANGES.parse().unwrap();
+    let hunk = patch[0][0].select(&[6, 7, 8]);
+
+    assert_eq!(
+        (1, 7, 1, 8),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!(1, hunk.removed());
+    assert_eq!(2, hunk.added());
+    assert!(hunk.lines()[1].is_context());
+    assert_eq!("two", hunk.lines()[1].value);
+    assert_eq!(
+        vec![(None, Some(6)), (Some(6), None), (None, Some(7))],
+        line_numbers(&hunk)[5..8]
+    );
+}
+
+#[test]
+fn test_select_no_newline_marker() {
+    let diff = "--- a/file
++++ b/file
+@@ -1 +1 @@
+-old
+\\ No newline at end of file
++new
+\\ No newline at end of file
+";
+    let patch: PatchSet = diff.parse().unwrap();
+
+    let kept = patch[0][0].select(&[]);
+    assert_eq!(2, kept.lines().len());
+    assert!(kept.lines()[0].is_context());
+    assert_eq!((1, 1), (kept.source_length, kept.target_length));
+
+    let removed = patch[0][0].select(&[0]);
+    assert_eq!(2, removed.lines().len());
+    assert!(removed.lines()[0].is_removed());
+    assert_eq!(
+        (1, 1, 0, 0),
+        (
+            removed.source_start,
+            removed.source_length,
+            removed.target_start,
+            removed.target_length
+        )
+    );
+}
+
+#[test]
+fn test_recount_hunk() {
+    let mut patch: PatchSet = TWO_CHANGES.parse().unwrap();
+    let hunk = &mut patch[0][0];
+    hunk[1].line_type = LineType::Context;
+    hunk.recount();
+
+    assert_eq!(
+        (1, 7, 1, 9),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!((1, 3), (hunk.removed(), hunk.added()));
+    assert_eq!(
+        vec![(Some(1), Some(1)), (Some(2), Some(2)), (None, Some(3))],
+        line_numbers(hunk)[..3]
+    );
+}
+
+#[test]
+fn test_inline_changes() {
+    let patch: PatchSet = "--- a/f
++++ b/f
+@@ -1,4 +1,4 @@
+-let total = count + 1;
+-let name = \"x\";
++let total = count + 2;
++let name = \"é\";
++extra();
+ done();
+"
+    .parse()
+    .unwrap();
+    let changes = patch[0][0].inline_changes(Granularity::Word);
+    assert_eq!(6, changes.len());
+    assert_eq!("let name = \"é\";", changes[3].0.value);
+    let ranges: Vec<_> = changes.into_iter().map(|(_, r)| r).collect();
+    assert_eq!(Some(vec![20..21]), ranges[0]);
+    assert_eq!(Some(vec![12..13]), ranges[1]);
+    assert_eq!(Some(vec![20..21]), ranges[2]);
+    assert_eq!(Some(vec![12..14]), ranges[3]);
+    assert_eq!(None, ranges[4]);
+    assert_eq!(None, ranges[5]);
+}
+
+#[test]
+fn test_changed_ranges_granularity() {
+    let old = Line::new("colour = red", LineType::Removed);
+    let new = Line::new("color = reed", LineType::Added);
+    assert_eq!(
+        (vec![0..6, 9..12], vec![0..5, 8..12]),
+        old.changed_ranges(&new, Granularity::Word)
+    );
+    assert_eq!(
+        (vec![4..5], vec![9..10]),
+        old.changed_ranges(&new, Granularity::Char)
+    );
+}
+
+#[test]
+fn test_changed_ranges_long_lines() {
+    // unrelated lines used to take memory quadratic in their length
+    let old = Line::new("ab".repeat(20_000).as_str(), LineType::Removed);
+    let new = Line::new("cd".repeat(20_000).as_str(), LineType::Added);
+    assert_eq!(
+        (vec![0..40_000], vec![0..40_000]),
+        old.changed_ranges(&new, Granularity::Char)
+    );
+}
@@ -0,0 +1,182 @@
+extern crate unidiff;
+
+use unidiff::{parse_log, range_diff, Commit, PatchSet};
+
+#[test]
+fn test_reverse() {
+    let patch: PatchSet = "diff --git a/old_name b/new_name
+old mode 100644
+new mode 100755
+rename from old_name
+rename to new_name
+index 1234567..89abcde
+--- a/old_name
++++ b/new_name
+@@ -1,3 +1,3 @@ intro
+ a
+-b
++B
+ c
+"
+    .parse()
+    .unwrap();
+
+    let reverse = patch.reverse();
+    let file = &reverse[0];
+    assert_eq!(
+        vec![
+            "diff --git a/new_name b/old_name",
+            "old mode 100755",
+            "new mode 100644",
+            "rename from new_name",
+            "rename to old_name",
+            "index 89abcde..1234567",
+        ],
+        file.header
+    );
+    assert_eq!("a/new_name", file.source_file);
+    assert_eq!("b/old_name", file.target_file);
+    assert_eq!("@@ -1,3 +1,3 @@ intro\n a\n-B\n+b\n c", file[0].to_string());
+    assert_eq!(patch.to_string(), reverse.reverse().to_string());
+}
+
+#[test]
+fn test_interdiff() {
+    let v1: PatchSet = "--- a/file
++++ b/file
+@@ -1,3 +1,3 @@
+ 1
+-2
++two
+ 3
+--- a/other
++++ b/other
+@@ -1 +1 @@
+-x
++y
+"
+    .parse()
+    .unwrap();
+    let v2: PatchSet = "--- a/file
++++ b/file
+@@ -1,3 +1,3 @@
+ 1
+-2
++two
+ 3
+@@ -8,3 +8,3 @@
+ 8
+-9
++nine
+ 10
+"
+    .parse()
+    .unwrap();
+
+    let changes = v1.interdiff(&v2).unwrap();
+    assert_eq!(
+        "--- a/file
++++ b/file
+@@ -8,3 +8,3 @@ 
+ 8
+-9
++nine
+ 10
+--- a/other
++++ b/other
+@@ -1,1 +1,1 @@ 
+-y
++x",
+        changes.to_string()
+    );
+}
+
+#[test]
+fn test_interdiff_header_only_files() {
+    let v1: PatchSet = "diff --git a/script.sh b/script.sh
+old mode 100644
+new mode 100755
+diff --git a/image.png b/image.png
+index 1111111..2222222 100644
+Binary files a/image.png and b/image.png differ
+diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -1 +1 @@
+-a
++b
+"
+    .parse()
+    .unwrap();
+    let v2: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n+b\n".parse().unwrap();
+
+    let changes = v1.interdiff(&v2).unwrap();
+    let paths: Vec<String> = changes.files().iter().map(|f| f.path()).collect();
+    assert_eq!(vec!["script.sh", "image.png"], paths);
+    assert!(changes.interdiff(&changes).unwrap().is_empty());
+}
+
+fn commit(id: &str, subject: &str, diff: &str) -> Commit {
+    let log = format!(
+        "commit {}\nAuthor: A U Thor <author@example.com>\n\n    {}\n\n{}",
+        id, subject, diff
+    );
+    parse_log(&log).unwrap().remove(0)
+}
+
+#[test]
+fn test_range_diff() {
+    let greeting = "--- a/hello\n+++ b/hello\n@@ -1 +1 @@\n-hello\n+hello, world\n";
+    let old = vec![
+        commit("1111111aaaa", "Add greeting", greeting),
+        commit(
+            "2222222bbbb",
+            "Fix typo",
+            "--- a/README\n+++ b/README\n@@ -1,2 +1,2 @@\n Title\n-Teh text\n+The text\n",
+        ),
+        commit(
+            "3333333cccc",
+            "Drop debug output",
+            "--- a/main.c\n+++ b/main.c\n@@ -4 +3,0 @@\n-\tdebug();\n",
+        ),
+    ];
+    let new = vec![
+        commit("4444444dddd", "Add greeting", greeting),
+        commit(
+            "5555555eeee",
+            "Fix typo",
+            "--- a/README\n+++ b/README\n@@ -1,2 +1,2 @@\n Title\n-Teh text\n+The texts\n",
+        ),
+        commit(
+            "6666666ffff",
+            "Add license",
+            "--- /dev/null\n+++ b/LICENSE\n@@ -0,0 +1 @@\n+MIT\n",
+        ),
+    ];
+
+    let entries = range_diff(&old, &new);
+    let statuses: Vec<char> = entries.iter().map(|e| e.status()).collect();
+    assert_eq!(vec!['=', '!', '<', '>'], statuses);
+    assert_eq!(
+        "1:  1111111 = 1:  4444444 Add greeting",
+        entries[0].to_string()
+    );
+    assert_eq!(
+        "2:  2222222 ! 2:  5555555 Fix typo
+    @@
+     @@
+      Title
+     -Teh text
+    -+The text
+    ++The texts",
+        entries[1].to_string()
+    );
+    assert_eq!(
+        "3:  3333333 < -:  ------- Drop debug output",
+        entries[2].to_string()
+    );
+    assert_eq!(
+        "-:  ------- > 3:  6666666 Add license",
+        entries[3].to_string()
+    );
+}
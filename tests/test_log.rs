@@ -0,0 +1,70 @@
+extern crate unidiff;
+
+use unidiff::parse_log;
+
+#[test]
+fn test_parse_git_log() {
+    let buf = include_str!("fixtures/git_log.diff");
+
+    let commits = parse_log(buf).unwrap();
+    assert_eq!(3, commits.len());
+
+    let first = &commits[0];
+    assert_eq!("5c2f0e1b7a4d9c3e8f6b1a2d4c6e8f0a1b3c5d7e", first.id);
+    assert_eq!(
+        Some("A U Thor <author@example.com>"),
+        first.author.as_deref()
+    );
+    assert_eq!(
+        Some("Sun Oct 13 20:51:40 2013 -0300"),
+        first.date.as_deref()
+    );
+    assert_eq!(
+        "Update and add files\n\nThe modified file gets a new paragraph.",
+        first.message
+    );
+    assert_eq!("Update and add files", first.subject());
+    assert_eq!(2, first.patch.len());
+    assert_eq!("added_file", first.patch[0].path());
+    assert_eq!(2, first.patch[0].added());
+    assert_eq!("modified_file", first.patch[1].path());
+    assert_eq!(2, first.patch[1].added());
+    assert_eq!(1, first.patch[1].removed());
+    // line numbers are relative to the whole log
+    assert_eq!(15, first.patch[0][0][0].diff_line_no);
+
+    let merge = &commits[1];
+    assert_eq!(vec!["44299fd", "13ba6cb"], merge.parents);
+    assert_eq!("Merge branch 'topic'", merge.message);
+    assert!(merge.patch.is_empty());
+
+    let last = &commits[2];
+    assert_eq!("Remove obsolete file", last.message);
+    assert_eq!(1, last.patch.removed_files().len());
+}
+
+#[test]
+fn test_parse_hg_log() {
+    let buf = include_str!("fixtures/hg_log.diff");
+
+    let commits = parse_log(buf).unwrap();
+    assert_eq!(2, commits.len());
+
+    assert_eq!("2:7a1b2c3d4e5f", commits[0].id);
+    assert_eq!(Some("tip"), commits[0].header("tag"));
+    assert_eq!("Update modified file", commits[0].message);
+    assert_eq!(1, commits[0].patch.modified_files().len());
+    assert_eq!("modified_file", commits[0].patch[0].path());
+
+    assert_eq!("1:44299fd3d1a8", commits[1].id);
+    assert_eq!(vec!["0:13ba6cbdb304"], commits[1].parents);
+    assert_eq!(
+        Some("C O Mitter <committer@example.com>"),
+        commits[1].author.as_deref()
+    );
+    assert_eq!(
+        "Add a file\n\nWith a longer description.",
+        commits[1].message
+    );
+    assert_eq!(1, commits[1].patch.added_files().len());
+}
@@ -0,0 +1,148 @@
+extern crate unidiff;
+
+use unidiff::{MoveOptions, PatchSet, WhitespaceMode};
+
+const MOVED: &str = "--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,8 +1,3 @@
+ use std::io;
+-
+-fn helper(x: u32) -> u32 {
+-    let y = x * 2;
+-    y + 1
+-}
+ 
+ fn main() {
+@@ -20,3 +15,4 @@
+ fn other() {
+-    old();
++    new();
++    more();
+ }
+--- a/src/util.rs
++++ b/src/util.rs
+@@ -1,2 +1,8 @@
+ // helpers
++
++fn helper(x: u32) -> u32 {
++        let y = x * 2;
++        y + 1
++}
++
+ pub fn noop() {}
+";
+
+fn move_ids(patch: &PatchSet, file: usize, hunk: usize) -> Vec<Option<usize>> {
+    patch[file][hunk]
+        .lines()
+        .iter()
+        .map(|l| l.move_id)
+        .collect()
+}
+
+#[test]
+fn test_detect_moves_exact() {
+    let mut patch: PatchSet = MOVED.parse().unwrap();
+    // the reindented body is not the same text
+    assert_eq!(0, patch.detect_moves(&MoveOptions::default()));
+
+    let options = MoveOptions {
+        min_lines: 2,
+        ..MoveOptions::default()
+    };
+    assert_eq!(1, patch.detect_moves(&options));
+    assert_eq!(
+        vec![None, Some(0), Some(0), None, None, None, None, None],
+        move_ids(&patch, 0, 0)
+    );
+    assert_eq!(
+        vec![None, Some(0), Some(0), None, None, None, None, None],
+        move_ids(&patch, 1, 0)
+    );
+    assert_eq!(vec![None; 5], move_ids(&patch, 0, 1));
+    // move ids annotate lines without changing them
+    let original: PatchSet = MOVED.parse().unwrap();
+    assert_eq!(original.files(), patch.files());
+}
+
+#[test]
+fn test_detect_moves_ignoring_whitespace() {
+    let mut patch: PatchSet = MOVED.parse().unwrap();
+    let options = MoveOptions {
+        min_lines: 3,
+        whitespace: WhitespaceMode::IgnoreAllSpace,
+    };
+    assert_eq!(1, patch.detect_moves(&options));
+    assert_eq!(
+        vec![
+            None,
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(0),
+            None,
+            None
+        ],
+        move_ids(&patch, 0, 0)
+    );
+    assert_eq!(
+        vec![
+            None,
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(0),
+            None,
+            None
+        ],
+        move_ids(&patch, 1, 0)
+    );
+}
+
+#[test]
+fn test_detect_moves_indentation_change() {
+    let mut patch: PatchSet = MOVED.parse().unwrap();
+    let options = MoveOptions {
+        min_lines: 3,
+        whitespace: WhitespaceMode::AllowIndentationChange,
+    };
+    // the body is indented more, but not the function lines around it
+    assert_eq!(0, patch.detect_moves(&options));
+
+    let mut patch: PatchSet = "--- a/a.py
++++ b/a.py
+@@ -1,4 +1,4 @@
+-if x:
+-    y()
+-    z()
++    if x:
++        y()
++        z()
+ end
+"
+    .parse()
+    .unwrap();
+    assert_eq!(1, patch.detect_moves(&options));
+    assert!(patch[0][0].lines()[..6]
+        .iter()
+        .all(|l| l.move_id == Some(0)));
+}
+
+#[test]
+fn test_detect_moves_resets_ids() {
+    let mut patch: PatchSet = MOVED.parse().unwrap();
+    let options = MoveOptions {
+        min_lines: 2,
+        whitespace: WhitespaceMode::IgnoreSpaceChange,
+    };
+    assert_eq!(1, patch.detect_moves(&options));
+    assert_eq!(0, patch.detect_moves(&MoveOptions::default()));
+    assert!(patch
+        .files()
+        .iter()
+        .flat_map(|f| f.hunks())
+        .flat_map(|h| h.lines())
+        .all(|l| l.move_id.is_none()));
+}
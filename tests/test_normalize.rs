@@ -0,0 +1,120 @@
+extern crate unidiff;
+
+use unidiff::{PatchSet, WhitespaceMode};
+
+const WIDE: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs\t2024-01-01 10:00:00
++++ b/src/lib.rs\t2024-01-02 10:00:00
+@@ -1,7 +1,7 @@
+ fn a() {}
+ fn b() {}
+ fn c() {}
+-fn d() {}
++fn d() { todo!() }
+ fn e() {}
+ fn f() {}
+ fn g() {}
+diff --git a/README b/README
+index 3333333..4444444 100644
+--- a/README
++++ b/README
+@@ -1 +1,2 @@
+ title
++text
+";
+
+const NARROW: &str = "--- a/README
++++ b/README
+@@ -1,0 +2 @@
++text
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -24,3 +24,3 @@ mod x
+ fn c() {}
+-fn d() {}
++fn d() { todo!() }
+ fn e() {}
+";
+
+#[test]
+fn test_equivalent_patches() {
+    let wide: PatchSet = WIDE.parse().unwrap();
+    let narrow: PatchSet = NARROW.parse().unwrap();
+    assert!(wide.is_equivalent(&narrow, WhitespaceMode::Exact));
+    assert_eq!(
+        wide.normalize(WhitespaceMode::Exact),
+        narrow.normalize(WhitespaceMode::Exact)
+    );
+    assert_eq!(
+        wide.normalize(WhitespaceMode::Exact).content_hash(),
+        narrow.normalize(WhitespaceMode::Exact).content_hash()
+    );
+}
+
+#[test]
+fn test_different_patches() {
+    let wide: PatchSet = WIDE.parse().unwrap();
+    let other: PatchSet = NARROW
+        .replace("todo!()", "unimplemented!()")
+        .parse()
+        .unwrap();
+    assert!(!wide.is_equivalent(&other, WhitespaceMode::Exact));
+    assert_ne!(
+        wide.normalize(WhitespaceMode::Exact).content_hash(),
+        other.normalize(WhitespaceMode::Exact).content_hash()
+    );
+
+    // a mode change is a change too
+    let mode: PatchSet = format!(
+        "diff --git a/run.sh b/run.sh\nold mode 100644\nnew mode 100755\n{}",
+        WIDE
+    )
+    .parse()
+    .unwrap();
+    assert!(!mode.is_equivalent(&wide, WhitespaceMode::Exact));
+}
+
+#[test]
+fn test_equivalent_ignoring_whitespace() {
+    let narrow: PatchSet = NARROW.parse().unwrap();
+    let reindented: PatchSet = NARROW
+        .replace("+fn d() { todo!() }", "+fn d() {  todo!()  }")
+        .replace(" fn e() {}\n", "-fn e() {}\n+  fn e() {}\n")
+        .parse()
+        .unwrap();
+    assert!(!narrow.is_equivalent(&reindented, WhitespaceMode::Exact));
+    assert!(narrow.is_equivalent(&reindented, WhitespaceMode::IgnoreAllSpace));
+    assert_eq!(
+        narrow
+            .normalize(WhitespaceMode::IgnoreAllSpace)
+            .content_hash(),
+        reindented
+            .normalize(WhitespaceMode::IgnoreAllSpace)
+            .content_hash()
+    );
+}
+
+#[test]
+fn test_missing_newline_is_a_change() {
+    let with_newline: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n+b\n".parse().unwrap();
+    let without: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n+b\n\\ No newline at end of file\n"
+        .parse()
+        .unwrap();
+    assert!(!with_newline.is_equivalent(&without, WhitespaceMode::Exact));
+    assert!(with_newline.is_equivalent(&without, WhitespaceMode::IgnoreSpaceAtEol));
+}
+
+#[test]
+fn test_content_hash_is_stable() {
+    let narrow: PatchSet = NARROW.parse().unwrap();
+    let hash = narrow.normalize(WhitespaceMode::Exact).content_hash();
+    // any change to the hashing scheme must show here
+    assert_eq!("1d755dbd104ff1bbe012e0fe063f8b89d5bce132", hash);
+    assert_ne!(
+        hash,
+        PatchSet::new()
+            .normalize(WhitespaceMode::Exact)
+            .content_hash()
+    );
+}
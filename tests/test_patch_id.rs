@@ -0,0 +1,92 @@
+extern crate unidiff;
+
+use unidiff::PatchSet;
+
+// ids from `git patch-id` 2.39 on the fixture
+const STABLE: &str = "4d4074b2255ec47a45b81172c8c1c64179533a10";
+const UNSTABLE: &str = "88783228c68b1ddd9301c1f6a220e0e031eee623";
+
+#[test]
+fn test_patch_id_matches_git() {
+    let patch: PatchSet = include_str!("fixtures/git_patch_id.diff").parse().unwrap();
+    assert_eq!(6, patch.len());
+    assert_eq!(
+        Some(STABLE),
+        patch.stable_patch_id().map(|id| id.to_string()).as_deref()
+    );
+    assert_eq!(
+        Some(UNSTABLE),
+        patch.patch_id().map(|id| id.to_string()).as_deref()
+    );
+    assert_eq!(
+        "3f615db3fbc13ce8c20313ad65b71656d779e84b",
+        patch[0].patch_id().unwrap().to_string()
+    );
+    assert_eq!(
+        "b673776c5a03bcc7b387c971bb367aecf38281d4",
+        patch[1].patch_id().unwrap().to_string()
+    );
+}
+
+#[test]
+fn test_stable_patch_id_ignores_file_order() {
+    let buf = include_str!("fixtures/git_patch_id.diff");
+    let files: Vec<&str> = buf.split("\ndiff --git").collect();
+    let reordered = format!(
+        "diff --git{}\ndiff --git{}\n",
+        files[2].trim_end(),
+        files[1].trim_end()
+    );
+    let original = format!(
+        "diff --git{}\ndiff --git{}\n",
+        files[1].trim_end(),
+        files[2].trim_end()
+    );
+    let reordered: PatchSet = reordered.parse().unwrap();
+    let original: PatchSet = original.parse().unwrap();
+    assert_eq!(original.stable_patch_id(), reordered.stable_patch_id());
+    assert_ne!(original.patch_id(), reordered.patch_id());
+}
+
+#[test]
+fn test_patch_id_ignores_whitespace_and_line_numbers() {
+    let patch: PatchSet = "diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -1,2 +1,2 @@
+ a
+-b
++c
+"
+    .parse()
+    .unwrap();
+    let shifted: PatchSet = "diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -40,2 +41,2 @@ fn main()
+ a
+-b
++ c
+"
+    .parse()
+    .unwrap();
+    let changed: PatchSet = "diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -1,2 +1,2 @@
+ a
+-b
++d
+"
+    .parse()
+    .unwrap();
+    assert_eq!(patch.patch_id(), shifted.patch_id());
+    assert_ne!(patch.patch_id(), changed.patch_id());
+}
+
+#[test]
+fn test_patch_id_needs_diff_line() {
+    let patch: PatchSet = "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n+b\n".parse().unwrap();
+    assert_eq!(None, patch.patch_id());
+    assert_eq!(None, PatchSet::new().stable_patch_id());
+}
@@ -1,6 +1,6 @@
 extern crate unidiff;
 
-use unidiff::{Hunk, PatchedFile};
+use unidiff::{Hunk, LineMapping, LineType, PatchSet, PatchedFile, PathStyle};
 
 #[test]
 fn test_is_added_file() {
@@ -22,3 +22,208 @@ fn test_is_modified_file() {
     let file = PatchedFile::with_hunks("a", "b", vec![hunk]);
     assert!(file.is_modified_file());
 }
+
+#[test]
+fn test_path_default_prefixes() {
+    let file = PatchedFile::new("a/src/lib.rs", "b/src/lib.rs");
+    assert_eq!("src/lib.rs", file.path());
+    assert_eq!(Some("src/lib.rs".to_owned()), file.source_path());
+    assert_eq!(Some("src/lib.rs".to_owned()), file.target_path());
+
+    let added = PatchedFile::new("/dev/null", "added_file");
+    assert_eq!("added_file", added.path());
+    assert_eq!(None, added.source_path());
+    assert_eq!(Some("added_file".to_owned()), added.target_path());
+
+    let removed = PatchedFile::new("a/removed_file", "/dev/null");
+    assert_eq!("removed_file", removed.path());
+    assert_eq!(None, removed.target_path());
+
+    // a top level directory called `a` is kept without a matching `b/`
+    let svn = PatchedFile::new("a/file", "a/file");
+    assert_eq!("a/file", svn.path());
+}
+
+#[test]
+fn test_path_custom_prefixes() {
+    let file = PatchedFile::new("old/src/lib.rs", "new/src/lib.rs");
+    let style = PathStyle::with_prefixes("old/", "new/");
+    assert_eq!("src/lib.rs", file.path_with(&style));
+    assert_eq!(Some("src/lib.rs".to_owned()), file.target_path_with(&style));
+
+    let file = PatchedFile::new("a/src/lib.rs", "b/src/lib.rs");
+    assert_eq!("a/src/lib.rs", file.path_with(&PathStyle::no_prefix()));
+}
+
+#[test]
+fn test_path_strip() {
+    let file = PatchedFile::new("/u/howard/src/blurfl/blurfl.c", "/dev/null");
+    assert_eq!(
+        "/u/howard/src/blurfl/blurfl.c",
+        file.path_with(&PathStyle::strip(0))
+    );
+    assert_eq!(
+        "u/howard/src/blurfl/blurfl.c",
+        file.path_with(&PathStyle::strip(1))
+    );
+    assert_eq!("blurfl/blurfl.c", file.path_with(&PathStyle::strip(4)));
+    assert_eq!("blurfl.c", file.path_with(&PathStyle::strip(10)));
+    assert_eq!(None, file.target_path_with(&PathStyle::strip(1)));
+
+    let file = PatchedFile::new("a//src/lib.rs", "b/src/lib.rs");
+    assert_eq!("src/lib.rs", file.path_with(&PathStyle::strip(1)));
+}
+
+#[test]
+fn test_recount_offsets() {
+    let diff = "--- a/file
++++ b/file
+@@ -1,2 +1,2 @@
+ a
+-b
++B
+@@ -10,2 +10,2 @@
+ j
+-k
++K
+";
+    let mut patch: PatchSet = diff.parse().unwrap();
+    patch[0][0][1].line_type = LineType::Added;
+    patch.recount();
+
+    let file = &patch[0];
+    assert_eq!(
+        (1, 1, 1, 3),
+        (
+            file[0].source_start,
+            file[0].source_length,
+            file[0].target_start,
+            file[0].target_length
+        )
+    );
+    assert_eq!(
+        (10, 2, 12, 2),
+        (
+            file[1].source_start,
+            file[1].source_length,
+            file[1].target_start,
+            file[1].target_length
+        )
+    );
+    assert_eq!(Some(12), file[1][0].target_line_no);
+    assert_eq!(3, file.added());
+    assert_eq!(1, file.removed());
+}
+
+const SOURCE: &str = "fn main() {
+    one();
+    two();
+    three();
+    four();
+    five();
+    six();
+    seven();
+}
+";
+
+#[test]
+fn test_set_context() {
+    let diff = "--- a/main.rs
++++ b/main.rs
+@@ -3 +3 @@ fn main() {
+-    two();
++    TWO();
+@@ -7 +7 @@ fn main() {
+-    six();
++    SIX();
+";
+    let mut patch: PatchSet = diff.parse().unwrap();
+    let file = &mut patch[0];
+
+    file.set_context(SOURCE, 2).unwrap();
+    assert_eq!(1, file.len());
+    assert_eq!(
+        (1, 9, 1, 9),
+        (
+            file[0].source_start,
+            file[0].source_length,
+            file[0].target_start,
+            file[0].target_length
+        )
+    );
+    assert_eq!("", file[0].section_header);
+    assert_eq!(Some(1), file[0][0].source_line_no);
+
+    file.set_context(SOURCE, 1).unwrap();
+    assert_eq!(2, file.len());
+    assert_eq!(
+        (2, 3, 2, 3),
+        (
+            file[0].source_start,
+            file[0].source_length,
+            file[0].target_start,
+            file[0].target_length
+        )
+    );
+    assert_eq!(
+        (6, 3, 6, 3),
+        (
+            file[1].source_start,
+            file[1].source_length,
+            file[1].target_start,
+            file[1].target_length
+        )
+    );
+    assert_eq!("fn main() {", file[1].section_header);
+    assert_eq!("    five();", file[1][0].value);
+}
+
+#[test]
+fn test_set_context_source_mismatch() {
+    let diff = "--- a/main.rs
++++ b/main.rs
+@@ -3 +3 @@
+-    three();
++    THREE();
+";
+    let mut patch: PatchSet = diff.parse().unwrap();
+    let before = patch[0].clone();
+    assert!(patch[0].set_context(SOURCE, 3).is_err());
+    assert_eq!(before, patch[0]);
+}
+
+#[test]
+fn test_map_lines() {
+    let diff = "--- a/file
++++ b/file
+@@ -2,4 +2,3 @@
+ 2
+-3
+-4
++three
+ 5
+@@ -10,2 +9,4 @@
+ 10
++10.1
++10.2
+ 11
+";
+    let patch: PatchSet = diff.parse().unwrap();
+    let file = &patch[0];
+
+    assert_eq!(LineMapping::Unchanged(1), file.map_source_to_target(1));
+    assert_eq!(LineMapping::Unchanged(2), file.map_source_to_target(2));
+    assert_eq!(LineMapping::Removed(3), file.map_source_to_target(3));
+    assert_eq!(LineMapping::Removed(3), file.map_source_to_target(4));
+    assert_eq!(LineMapping::Unchanged(4), file.map_source_to_target(5));
+    assert_eq!(LineMapping::Unchanged(8), file.map_source_to_target(9));
+    assert_eq!(LineMapping::Unchanged(12), file.map_source_to_target(11));
+    assert_eq!(Some(21), file.map_source_to_target(20).line());
+
+    assert_eq!(LineMapping::Added(5), file.map_target_to_source(3));
+    assert_eq!(LineMapping::Unchanged(5), file.map_target_to_source(4));
+    assert_eq!(LineMapping::Added(11), file.map_target_to_source(10));
+    assert_eq!(LineMapping::Unchanged(11), file.map_target_to_source(12));
+    assert_eq!(None, file.map_target_to_source(11).line());
+    assert_eq!(LineMapping::Unchanged(20), file.map_target_to_source(21));
+}
@@ -1,13 +1,13 @@
 extern crate unidiff;
 
-use unidiff::PatchSet;
+use unidiff::{LineType, PatchSet, PatchedFile, VcsHeader};
 
 #[test]
 fn test_parse_sample0_diff() {
     let buf = include_str!("fixtures/sample0.diff");
 
     let mut patch = PatchSet::new();
-    patch.parse(&buf).unwrap();
+    patch.parse(buf).unwrap();
 
     // three file in the patch
     assert_eq!(3, patch.len());
@@ -54,7 +54,7 @@ fn test_parse_git_diff() {
     let buf = include_str!("fixtures/git.diff");
 
     let mut patch = PatchSet::new();
-    patch.parse(&buf).unwrap();
+    patch.parse(buf).unwrap();
 
     assert_eq!(3, patch.len());
 
@@ -82,7 +82,7 @@ fn test_parse_bzr_diff() {
     let buf = include_str!("fixtures/bzr.diff");
 
     let mut patch = PatchSet::new();
-    patch.parse(&buf).unwrap();
+    patch.parse(buf).unwrap();
 
     assert_eq!(3, patch.len());
 
@@ -110,7 +110,7 @@ fn test_parse_hg_diff() {
     let buf = include_str!("fixtures/hg.diff");
 
     let mut patch = PatchSet::new();
-    patch.parse(&buf).unwrap();
+    patch.parse(buf).unwrap();
 
     assert_eq!(3, patch.len());
 
@@ -138,7 +138,7 @@ fn test_parse_svn_diff() {
     let buf = include_str!("fixtures/svn.diff");
 
     let mut patch = PatchSet::new();
-    patch.parse(&buf).unwrap();
+    patch.parse(buf).unwrap();
 
     assert_eq!(3, patch.len());
 
@@ -166,7 +166,7 @@ fn test_parse_line_numbers() {
     let buf = include_str!("fixtures/sample0.diff");
 
     let mut patch = PatchSet::new();
-    patch.parse(&buf).unwrap();
+    patch.parse(buf).unwrap();
 
     let mut target_line_nos = vec![];
     let mut source_line_nos = vec![];
@@ -175,8 +175,8 @@ fn test_parse_line_numbers() {
     for diff_file in patch {
         for hunk in diff_file {
             for line in hunk {
-                source_line_nos.push(line.source_line_no.clone());
-                target_line_nos.push(line.target_line_no.clone());
+                source_line_nos.push(line.source_line_no);
+                target_line_nos.push(line.target_line_no);
                 diff_line_nos.push(line.diff_line_no);
             }
         }
@@ -333,7 +333,7 @@ fn test_single_line_diff() {
         let buf = include_str!("fixtures/sample4.diff");
 
         let mut patch = PatchSet::new();
-        patch.parse(&buf).unwrap();
+        patch.parse(buf).unwrap();
 
         assert_eq!(1, patch.len());
 
@@ -347,7 +347,7 @@ fn test_single_line_diff() {
         let buf = include_str!("fixtures/sample5.diff");
 
         let mut patch = PatchSet::new();
-        patch.parse(&buf).unwrap();
+        patch.parse(buf).unwrap();
 
         assert_eq!(1, patch.len());
 
@@ -364,7 +364,7 @@ fn test_single_line_diff_with_trailer() {
     let buf = include_str!("fixtures/sample4-plus.diff");
 
     let mut patch = PatchSet::new();
-    patch.parse(&buf).unwrap();
+    patch.parse(buf).unwrap();
 
     assert_eq!(3, patch.len());
 
@@ -394,3 +394,222 @@ fn test_parse_patchset_from_str() {
     // three hunks
     assert_eq!(3, patch[0].len());
 }
+
+#[test]
+fn test_parse_preamble() {
+    let buf = include_str!("fixtures/sample2.diff");
+
+    let patch: PatchSet = buf.parse().unwrap();
+
+    assert_eq!(
+        vec![
+            "# HG changeset patch",
+            "# Parent 13ba6cbdb304cd251fbc22466cadb21019ee817f",
+            "# User Bill McCloskey <wmccloskey@mozilla.com>",
+            "",
+        ],
+        patch.preamble()
+    );
+    assert_eq!(
+        vec!["diff --git a/js/src/jsfriendapi.cpp b/js/src/jsfriendapi.cpp"],
+        patch[1].header
+    );
+    assert!(patch.epilogue().is_empty());
+    // nothing is lost when writing it back out
+    assert_eq!(buf, patch.to_string());
+}
+
+#[test]
+fn test_parse_epilogue() {
+    let buf = include_str!("fixtures/bzr.diff");
+
+    let patch: PatchSet = buf.parse().unwrap();
+
+    assert!(patch.preamble().is_empty());
+    assert_eq!(vec!["", ""], patch.epilogue());
+    // the trailing "no newline" marker belongs to the last hunk
+    let last = patch[2][0].lines().last().unwrap();
+    assert_eq!(LineType::Empty, last.line_type);
+    assert_eq!("\\ No newline at end of file", last.to_string());
+}
+
+#[test]
+fn test_parse_vcs_headers() {
+    let svn: PatchSet = include_str!("fixtures/svn.diff").parse().unwrap();
+    assert_eq!(
+        vec![
+            "Index: removed_file",
+            "===================================================================",
+        ],
+        svn[1].header
+    );
+    assert_eq!(
+        Some(VcsHeader::Svn {
+            path: "removed_file".to_owned()
+        }),
+        svn[1].vcs_header()
+    );
+
+    let bzr: PatchSet = include_str!("fixtures/bzr.diff").parse().unwrap();
+    assert_eq!(
+        Some(VcsHeader::Bzr {
+            action: "modified".to_owned(),
+            path: "modified_file".to_owned(),
+            target: None,
+        }),
+        bzr[1].vcs_header()
+    );
+
+    let hg: PatchSet = include_str!("fixtures/hg.diff").parse().unwrap();
+    assert_eq!(
+        Some(VcsHeader::Hg {
+            revisions: vec!["44299fd3d1a8".to_owned()],
+            path: "added_file".to_owned(),
+        }),
+        hg[0].vcs_header()
+    );
+
+    let git: PatchSet = include_str!("fixtures/git.diff").parse().unwrap();
+    assert_eq!(
+        vec![
+            "diff --git a/added_file b/added_file",
+            "new file mode 100644",
+            "index 0000000..9b710f3",
+        ],
+        git[0].header
+    );
+    assert_eq!(
+        Some(VcsHeader::Git {
+            source: "a/added_file".to_owned(),
+            target: "b/added_file".to_owned(),
+        }),
+        git[0].vcs_header()
+    );
+
+    let plain: PatchSet = include_str!("fixtures/sample0.diff").parse().unwrap();
+    assert!(plain[0].header.is_empty());
+    assert_eq!(None, plain[0].vcs_header());
+}
+
+#[test]
+fn test_parse_quoted_filenames() {
+    let buf = include_str!("fixtures/git_quoted.diff");
+
+    let patch: PatchSet = buf.parse().unwrap();
+    assert_eq!(3, patch.len());
+
+    assert_eq!("a/foo\tbar.txt", patch[0].source_file);
+    assert_eq!("foo\tbar.txt", patch[0].path());
+    assert_eq!(
+        Some(VcsHeader::Git {
+            source: "a/foo\tbar.txt".to_owned(),
+            target: "b/foo\tbar.txt".to_owned(),
+        }),
+        patch[0].vcs_header()
+    );
+
+    assert!(patch[1].is_added_file());
+    assert_eq!("café.txt", patch[1].path());
+
+    assert_eq!("with space.txt", patch[2].path());
+    assert_eq!(
+        Some(VcsHeader::Git {
+            source: "a/with space.txt".to_owned(),
+            target: "b/with space.txt".to_owned(),
+        }),
+        patch[2].vcs_header()
+    );
+
+    // names are quoted again on output
+    let output = patch.to_string();
+    assert!(output.contains("--- \"a/foo\\tbar.txt\"\n+++ \"b/foo\\tbar.txt\"\n"));
+    assert!(output.contains("+++ \"b/caf\\303\\251.txt\"\n"));
+    assert!(output.contains("--- a/with space.txt\n"));
+}
+
+#[test]
+fn test_unquoted_utf8_filenames_round_trip() {
+    let buf = "--- a/café.txt\n+++ b/café.txt\n@@ -1,1 +1,1 @@ intro\n-old\n+new";
+
+    let patch: PatchSet = buf.parse().unwrap();
+    assert_eq!("café.txt", patch[0].path());
+    assert_eq!(buf, patch.to_string());
+    assert_eq!(
+        "--- a/café.txt\n+++ b/café.txt\n@@ -1,1 +1,1 @@ intro\n-new\n+old",
+        patch.reverse().to_string()
+    );
+
+    // names that cannot be written unquoted are quoted anyway
+    let file = PatchedFile::new("a/say \"hi\"", "b/say \"hi\"");
+    assert!(file.to_string().starts_with("--- \"a/say \\\"hi\\\"\"\n"));
+}
+
+#[test]
+fn test_parse_header_only_files() {
+    let buf = include_str!("fixtures/git_header_only.diff");
+
+    let patch: PatchSet = buf.parse().unwrap();
+    assert_eq!(4, patch.len());
+
+    assert!(patch[0].is_binary());
+    assert!(patch[0].is_added_file());
+    assert_eq!("/dev/null", patch[0].source_file);
+    assert_eq!("logo.png", patch[0].path());
+    assert_eq!(
+        vec![
+            "diff --git a/logo.png b/logo.png",
+            "new file mode 100644",
+            "index 0000000..b2f3c4d",
+            "Binary files /dev/null and b/logo.png differ",
+        ],
+        patch[0].header
+    );
+
+    assert!(!patch[1].is_binary());
+    assert!(patch[1].is_modified_file());
+    assert_eq!("a/src/old/lib.rs", patch[1].source_file);
+    assert_eq!("b/src/new/lib.rs", patch[1].target_file);
+    assert_eq!(0, patch[1].len());
+
+    assert!(patch[2].is_modified_file());
+    assert_eq!(
+        vec![
+            "diff --git a/run.sh b/run.sh",
+            "old mode 100644",
+            "new mode 100755"
+        ],
+        patch[2].header
+    );
+    assert_eq!(0, patch[2].len());
+
+    // the header of the next file no longer takes them in
+    assert_eq!(2, patch[3].header.len());
+    assert_eq!(1, patch[3].len());
+    assert_eq!(buf, format!("{}\n", patch));
+}
+
+#[test]
+fn test_parse_header_only_last_file() {
+    let buf = "\
+diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -1,1 +1,1 @@ fn f()
+-a
++b
+diff --git a/gone.bin b/gone.bin
+deleted file mode 100644
+index b2f3c4d..0000000
+Binary files a/gone.bin and /dev/null differ
+";
+
+    let patch: PatchSet = buf.parse().unwrap();
+    assert_eq!(2, patch.len());
+    assert!(patch.epilogue().is_empty());
+    assert!(patch[1].is_binary());
+    assert!(patch[1].is_removed_file());
+    assert_eq!("/dev/null", patch[1].target_file);
+    assert_eq!("gone.bin", patch[1].path());
+    assert_eq!(vec![patch[1].clone()], patch.removed_files());
+    assert_eq!(buf, format!("{}\n", patch));
+}
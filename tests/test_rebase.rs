@@ -0,0 +1,268 @@
+extern crate unidiff;
+
+use unidiff::{Conflict, ConflictKind, PatchSet};
+
+const OURS: &str = "--- a/file
++++ b/file
+@@ -5,3 +5,3 @@
+ 5
+-6
++six
+ 7
+@@ -20,2 +20,3 @@
+ 20
++20.5
+ 21
+--- a/other
++++ b/other
+@@ -1 +1 @@
+-x
++y
+";
+
+#[test]
+fn test_rebase_shifts_hunks() {
+    let ours: PatchSet = OURS.parse().unwrap();
+    let theirs: PatchSet = "--- a/file
++++ b/file
+@@ -1,3 +1,2 @@
+ 1
+-2
+ 3
+@@ -12,3 +11,5 @@
+ 12
++12.1
++12.2
+ 13
+ 14
+"
+    .parse()
+    .unwrap();
+
+    let rebased = ours.rebase(&theirs).unwrap();
+    let file = &rebased[0];
+    assert_eq!((4, 4), (file[0].source_start, file[0].target_start));
+    assert_eq!((21, 21), (file[1].source_start, file[1].target_start));
+    assert_eq!(Some(22), file[1][1].target_line_no);
+    assert_eq!(ours[1], rebased[1]);
+}
+
+#[test]
+fn test_rebase_conflicts() {
+    let ours: PatchSet = OURS.parse().unwrap();
+    let theirs: PatchSet = "--- a/file
++++ b/file
+@@ -7,2 +7,2 @@
+-7
++seven
+ 8
+@@ -20,2 +20,3 @@
+ 20
++20.25
+ 21
+"
+    .parse()
+    .unwrap();
+
+    let conflicts = ours.rebase(&theirs).unwrap_err();
+    assert_eq!(
+        vec![
+            Conflict {
+                path: "file".to_owned(),
+                kind: ConflictKind::Adjacent,
+                file_index: 0,
+                hunk_index: Some(0),
+                range: (6, 7),
+                other_file_index: 0,
+                other_hunk_index: Some(0),
+                other_range: (7, 8),
+            },
+            Conflict {
+                path: "file".to_owned(),
+                kind: ConflictKind::Overlapping,
+                file_index: 0,
+                hunk_index: Some(1),
+                range: (21, 21),
+                other_file_index: 0,
+                other_hunk_index: Some(1),
+                other_range: (21, 21),
+            },
+        ],
+        conflicts
+    );
+}
+
+#[test]
+fn test_rebase_adjacent_hunks() {
+    let ours: PatchSet = OURS.parse().unwrap();
+    let theirs: PatchSet = "--- a/file
++++ b/file
+@@ -8,2 +8 @@
+-8
+ 9
+"
+    .parse()
+    .unwrap();
+
+    let rebased = ours.rebase(&theirs).unwrap();
+    assert_eq!(
+        (5, 5),
+        (rebased[0][0].source_start, rebased[0][0].target_start)
+    );
+    assert_eq!(
+        (19, 19),
+        (rebased[0][1].source_start, rebased[0][1].target_start)
+    );
+}
+
+#[test]
+fn test_conflicts_kinds() {
+    let ours: PatchSet = OURS.parse().unwrap();
+    let theirs: PatchSet = "--- a/other
++++ b/other
+@@ -1 +1 @@
+-x
++y
+--- a/file
++++ b/file
+@@ -5,2 +5,2 @@
+-5
++five
+ 6
+@@ -20,2 +20,3 @@
+ 20
++20.5
+ 21
+@@ -40 +40 @@
+-40
++forty
+"
+    .parse()
+    .unwrap();
+
+    let conflicts = ours.conflicts(&theirs);
+    let summary: Vec<_> = conflicts
+        .iter()
+        .map(|c| {
+            (
+                c.path.as_str(),
+                c.kind,
+                (c.file_index, c.hunk_index),
+                (c.other_file_index, c.other_hunk_index),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("file", ConflictKind::Adjacent, (0, Some(0)), (1, Some(0))),
+            ("file", ConflictKind::Identical, (0, Some(1)), (1, Some(1))),
+            ("other", ConflictKind::Identical, (1, Some(0)), (0, Some(0))),
+        ],
+        summary
+    );
+    assert_eq!((6, 7), conflicts[0].range);
+    assert_eq!((5, 6), conflicts[0].other_range);
+}
+
+#[test]
+fn test_conflicts_touching_changes() {
+    let ours: PatchSet = "--- a/f\n+++ b/f\n@@ -3 +3 @@\n-c\n+C\n".parse().unwrap();
+    let touching: PatchSet = "--- a/f\n+++ b/f\n@@ -4 +4 @@\n-d\n+D\n".parse().unwrap();
+    let apart: PatchSet = "--- a/f\n+++ b/f\n@@ -5 +5 @@\n-e\n+E\n".parse().unwrap();
+    let conflicts = ours.conflicts(&touching);
+    assert_eq!(1, conflicts.len());
+    assert_eq!(ConflictKind::Adjacent, conflicts[0].kind);
+    assert!(ours.conflicts(&apart).is_empty());
+    assert!(ours.rebase(&apart).is_ok());
+}
+
+#[test]
+fn test_rebase_insertion_after_hunk() {
+    let ours: PatchSet = "--- a/f\n+++ b/f\n@@ -3,3 +3,3 @@\n l2\n l3\n-l4\n+X\n"
+        .parse()
+        .unwrap();
+    let theirs: PatchSet = "--- a/f\n+++ b/f\n@@ -5,0 +6 @@\n+new\n".parse().unwrap();
+
+    let conflicts = ours.rebase(&theirs).unwrap_err();
+    assert_eq!(ours.conflicts(&theirs), conflicts);
+    assert_eq!(ConflictKind::Adjacent, conflicts[0].kind);
+    assert_eq!(
+        ((5, 6), (6, 6)),
+        (conflicts[0].range, conflicts[0].other_range)
+    );
+
+    // an insertion right before the context shifts the hunk
+    let before: PatchSet = "--- a/f\n+++ b/f\n@@ -2,0 +3 @@\n+new\n".parse().unwrap();
+    assert!(ours.conflicts(&before).is_empty());
+    let rebased = ours.rebase(&before).unwrap();
+    assert_eq!(
+        (4, 4),
+        (rebased[0][0].source_start, rebased[0][0].target_start)
+    );
+}
+
+#[test]
+fn test_conflicts_added_files() {
+    let ours: PatchSet = "--- /dev/null\n+++ b/new\n@@ -0,0 +1 @@\n+ours\n"
+        .parse()
+        .unwrap();
+    let theirs: PatchSet = "--- /dev/null\n+++ b/new\n@@ -0,0 +1 @@\n+theirs\n"
+        .parse()
+        .unwrap();
+
+    let conflicts = ours.conflicts(&theirs);
+    assert_eq!(1, conflicts.len());
+    assert_eq!("new", conflicts[0].path);
+    assert_eq!(ConflictKind::Overlapping, conflicts[0].kind);
+    assert_eq!(conflicts, ours.rebase(&theirs).unwrap_err());
+    assert_eq!(ConflictKind::Identical, ours.conflicts(&ours)[0].kind);
+}
+
+#[test]
+fn test_conflicts_without_hunks() {
+    let ours: PatchSet = "diff --git a/logo.png b/logo.png
+index 1111111..2222222 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+diff --git a/old.txt b/new.txt
+similarity index 100%
+rename from old.txt
+rename to new.txt
+"
+    .parse()
+    .unwrap();
+    let theirs: PatchSet = "diff --git a/logo.png b/logo.png
+index 1111111..3333333 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100600
+diff --git a/old.txt b/other.txt
+similarity index 100%
+rename from old.txt
+rename to other.txt
+"
+    .parse()
+    .unwrap();
+
+    let conflicts = ours.conflicts(&theirs);
+    let summary: Vec<_> = conflicts
+        .iter()
+        .map(|c| (c.path.as_str(), c.kind, c.hunk_index, c.range))
+        .collect();
+    assert_eq!(
+        vec![
+            ("logo.png", ConflictKind::Overlapping, None, (0, 0)),
+            ("run.sh", ConflictKind::Overlapping, None, (0, 0)),
+            ("old.txt", ConflictKind::Overlapping, None, (0, 0)),
+        ],
+        summary
+    );
+    assert_eq!(conflicts, ours.rebase(&theirs).unwrap_err());
+
+    // the same changes on both sides do not conflict
+    assert!(ours.conflicts(&ours).is_empty());
+    assert!(ours.rebase(&ours).is_ok());
+}
@@ -0,0 +1,61 @@
+extern crate unidiff;
+
+use unidiff::{PatchSet, Side};
+
+const DIFF: &str = "--- a/first
++++ b/first
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+@@ -10,2 +10,3 @@ section
+ ten
++ten and a half
+ eleven
+--- a/second
++++ b/second
+@@ -1 +1 @@
+-old
+\\ No newline at end of file
++new
+";
+
+#[test]
+fn test_file_positions() {
+    let patch: PatchSet = DIFF.parse().unwrap();
+    let file = &patch[0];
+
+    assert_eq!(Some(1), file.position(Side::Left, 1));
+    assert_eq!(Some(1), file.position(Side::Right, 1));
+    assert_eq!(Some(2), file.position(Side::Left, 2));
+    assert_eq!(Some(3), file.position(Side::Right, 2));
+    assert_eq!(Some(7), file.position(Side::Right, 11));
+    assert_eq!(Some(8), file.position(Side::Left, 11));
+    assert_eq!(None, file.position(Side::Left, 12));
+    assert!(file.is_commentable(Side::Right, 12));
+    assert!(!file.is_commentable(Side::Right, 5));
+
+    assert_eq!(Some((Side::Left, 2)), file.line_at_position(2));
+    assert_eq!(Some((Side::Right, 3)), file.line_at_position(4));
+    assert_eq!(None, file.line_at_position(5));
+    assert_eq!(Some((Side::Right, 10)), file.line_at_position(6));
+    assert_eq!(None, file.line_at_position(9));
+    assert_eq!("RIGHT", Side::Right.to_string());
+}
+
+#[test]
+fn test_diff_positions() {
+    let patch: PatchSet = DIFF.parse().unwrap();
+
+    assert_eq!(Some((0, 1)), patch.position_of_diff_line(4));
+    assert_eq!(Some((0, 6)), patch.position_of_diff_line(9));
+    assert_eq!(None, patch.position_of_diff_line(8));
+    assert_eq!(Some((1, 3)), patch.position_of_diff_line(17));
+
+    assert_eq!(Some(9), patch.diff_line_at_position(0, 6));
+    assert_eq!(Some(16), patch.diff_line_at_position(1, 2));
+    assert_eq!(None, patch.diff_line_at_position(0, 5));
+    assert_eq!(None, patch.diff_line_at_position(2, 1));
+    assert_eq!(None, patch[1].line_at_position(2));
+}
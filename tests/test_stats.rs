@@ -0,0 +1,155 @@
+extern crate unidiff;
+
+use unidiff::{ChangeStats, PatchSet};
+
+const PATCH: &str = "--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,4 @@
+-fn main() {
++fn main()  {
+     run();
+-    // start
++    // start here
+ }
+@@ -10,2 +10,4 @@
+ fn run() {
++    go();
++
+ }
+--- a/src/util/mod.rs
++++ b/src/util/mod.rs
+@@ -1 +1,2 @@
+ pub mod a;
++pub mod b;
+--- a/README.md
++++ b/README.md
+@@ -1,2 +1,3 @@
+ # Title
++
+ Text
+--- a/Makefile
++++ b/Makefile
+@@ -1 +1 @@
+-all:
++all: build
+";
+
+#[test]
+fn test_file_change_stats() {
+    let patch: PatchSet = PATCH.parse().unwrap();
+    assert_eq!(
+        ChangeStats {
+            files: 1,
+            hunks: 2,
+            added: 4,
+            removed: 2,
+            whitespace_only: 2,
+            comment_only: 2,
+        },
+        patch[0].change_stats()
+    );
+    assert_eq!(1, patch[2].change_stats().whitespace_only);
+}
+
+#[test]
+fn test_comment_syntax_by_language() {
+    let patch: PatchSet = "--- a/a.rs
++++ b/a.rs
+@@ -1 +1 @@
+-#[derive(Debug)]
++#[derive(Clone)]
+--- a/a.c
++++ b/a.c
+@@ -1,3 +1,3 @@
+-#include <a.h>
++#include <b.h>
+ int f() {
+-    *p = 1;
+-    --i;
++    *p = 2;
++    --j;
+--- a/a.py
++++ b/a.py
+@@ -1 +1 @@
+-# note
++# notes
+--- a/a.sql
++++ b/a.sql
+@@ -1 +1 @@
+--- note
++-- notes
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-// note
++// notes
+"
+    .parse()
+    .unwrap();
+    let comment_only: Vec<usize> = patch
+        .files()
+        .iter()
+        .map(|f| f.change_stats().comment_only)
+        .collect();
+    assert_eq!(vec![0, 0, 2, 2, 0], comment_only);
+}
+
+#[test]
+fn test_patchset_change_stats() {
+    let patch: PatchSet = PATCH.parse().unwrap();
+    let stats = patch.change_stats();
+    assert_eq!(4, stats.files);
+    assert_eq!(5, stats.hunks);
+    assert_eq!(10, stats.churn());
+    assert_eq!(3, stats.whitespace_only);
+    assert_eq!(2, stats.comment_only);
+}
+
+#[test]
+fn test_stats_by_directory() {
+    let patch: PatchSet = PATCH.parse().unwrap();
+    let stats = patch.stats_by_directory();
+    assert_eq!(
+        vec!["", "src", "src/util"],
+        stats.keys().map(String::as_str).collect::<Vec<_>>()
+    );
+    assert_eq!(2, stats[""].files);
+    assert_eq!(3, stats[""].churn());
+    assert_eq!(6, stats["src"].churn());
+    assert_eq!(1, stats["src/util"].added);
+}
+
+#[test]
+fn test_stats_by_extension() {
+    let patch: PatchSet = PATCH.parse().unwrap();
+    let stats = patch.stats_by_extension();
+    assert_eq!(
+        vec!["", "md", "rs"],
+        stats.keys().map(String::as_str).collect::<Vec<_>>()
+    );
+    assert_eq!(2, stats["rs"].files);
+    assert_eq!(2, stats[""].churn());
+}
+
+#[test]
+fn test_stats_by_classifier() {
+    let patch: PatchSet = PATCH.parse().unwrap();
+    let stats = patch.stats_by(|f| f.path().starts_with("src/"));
+    assert_eq!(7, stats[&true].churn());
+    assert_eq!(3, stats[&false].churn());
+}
+
+#[test]
+fn test_dirstat() {
+    let patch: PatchSet = PATCH.parse().unwrap();
+    assert_eq!(
+        "  10.0% src/util/\n  60.0% src/\n",
+        patch.dirstat(3.0, false)
+    );
+    assert_eq!(
+        "  10.0% src/util/\n  70.0% src/\n",
+        patch.dirstat(3.0, true)
+    );
+    assert_eq!("  70.0% src/\n", patch.dirstat(20.0, false));
+    assert_eq!("", PatchSet::new().dirstat(3.0, false));
+}
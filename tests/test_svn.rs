@@ -0,0 +1,82 @@
+extern crate unidiff;
+
+use unidiff::{PatchSet, PropertyAction, SvnRevision};
+
+#[test]
+fn test_parse_svn_revisions() {
+    let buf = include_str!("fixtures/svn.diff");
+
+    let patch: PatchSet = buf.parse().unwrap();
+
+    assert_eq!(Some(SvnRevision::Number(191)), patch[0].source_revision);
+    assert_eq!(Some(SvnRevision::WorkingCopy), patch[0].target_revision);
+    assert_eq!(None, patch[0].source_timestamp);
+    assert_eq!(None, patch[0].target_timestamp);
+    assert_eq!(Some(SvnRevision::Number(0)), patch[2].source_revision);
+
+    assert_eq!(
+        Some(SvnRevision::Nonexistent),
+        SvnRevision::parse("(nonexistent)")
+    );
+    assert_eq!(None, SvnRevision::parse("2013-10-13 23:53:13 +0000"));
+    assert_eq!("(revision 42)", SvnRevision::Number(42).to_string());
+}
+
+#[test]
+fn test_parse_svn_properties() {
+    let buf = include_str!("fixtures/svn_properties.diff");
+
+    let patch: PatchSet = buf.parse().unwrap();
+    assert_eq!(3, patch.len());
+
+    let modified = &patch[0];
+    assert_eq!(1, modified.len());
+    assert_eq!(2, modified.properties.len());
+    assert_eq!(PropertyAction::Added, modified.properties[0].action);
+    assert_eq!("svn:eol-style", modified.properties[0].name);
+    assert_eq!(None, modified.properties[0].old_value());
+    assert_eq!(
+        Some("native".to_owned()),
+        modified.properties[0].new_value()
+    );
+    assert_eq!(PropertyAction::Modified, modified.properties[1].action);
+    assert_eq!(Some("Id".to_owned()), modified.properties[1].old_value());
+    assert_eq!(
+        Some("Id Rev".to_owned()),
+        modified.properties[1].new_value()
+    );
+
+    let added = &patch[1];
+    assert!(added.is_added_file());
+    assert!(added.properties.is_empty());
+    assert_eq!(Some(SvnRevision::Nonexistent), added.source_revision);
+
+    let docs = &patch[2];
+    assert!(docs.is_empty());
+    assert_eq!("docs", docs.path());
+    assert_eq!(PropertyAction::Deleted, docs.properties[0].action);
+    assert_eq!(
+        Some("*.o\n*.tmp".to_owned()),
+        docs.properties[0].old_value()
+    );
+    assert_eq!(None, docs.properties[0].new_value());
+}
+
+#[test]
+fn test_property_only_change() {
+    let buf = "Index: docs
+===================================================================
+
+Property changes on: docs
+___________________________________________________________________
+Added: svn:ignore
+## -0,0 +1 ##
++build
+";
+
+    let patch: PatchSet = buf.parse().unwrap();
+    assert_eq!(1, patch.len());
+    assert_eq!("docs", patch[0].path());
+    assert_eq!(Some("build".to_owned()), patch[0].properties[0].new_value());
+    assert_eq!(buf.trim_end(), patch.to_string());
+}
@@ -0,0 +1,123 @@
+extern crate unidiff;
+
+use unidiff::{LineType, PatchSet, WordDiffFormat, WordSegment};
+
+fn segments(line: &unidiff::Line) -> Vec<(LineType, &str)> {
+    line.segments
+        .iter()
+        .map(|s| (s.line_type.clone(), s.value.as_str()))
+        .collect()
+}
+
+fn check_word_diff(patch: &PatchSet) {
+    assert_eq!(2, patch.len());
+
+    let hunk = &patch[0][0];
+    assert_eq!(
+        (1, 2, 1, 3),
+        (
+            hunk.source_start,
+            hunk.source_length,
+            hunk.target_start,
+            hunk.target_length
+        )
+    );
+    assert_eq!(
+        vec![" one", "-two", "+2", "+three"],
+        hunk.lines()
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(vec![(LineType::Removed, "two")], segments(&hunk[1]));
+    assert_eq!(vec![(LineType::Added, "three")], segments(&hunk[3]));
+
+    let hunk = &patch[1][0];
+    assert_eq!((6, 6), (hunk.source_length, hunk.target_length));
+    assert_eq!((3, 3), (hunk.removed(), hunk.added()));
+    assert_eq!("The quick brown fox", hunk[2].value);
+    assert_eq!(Some(3), hunk[2].source_line_no);
+    assert_eq!(
+        vec![
+            (LineType::Context, "The quick "),
+            (LineType::Added, "red"),
+            (LineType::Context, " fox"),
+        ],
+        segments(&hunk[3])
+    );
+    assert_eq!("jumps over the dog.", hunk[5].value);
+    assert_eq!(Some(4), hunk[5].target_line_no);
+    assert_eq!("Brand new line", hunk[8].value);
+    assert_eq!(
+        vec![WordSegment {
+            line_type: LineType::Context,
+            value: "Unchanged line".to_owned(),
+        }],
+        hunk[6].segments
+    );
+    assert_eq!("", hunk[1].value);
+}
+
+#[test]
+fn test_parse_word_diff_plain() {
+    let buf = include_str!("fixtures/word_diff_plain.diff");
+    let mut patch = PatchSet::new();
+    patch.parse_word_diff(buf, WordDiffFormat::Plain).unwrap();
+    check_word_diff(&patch);
+    assert_eq!(7, patch[0][0][2].diff_line_no);
+    assert_eq!(7, patch[0][0][1].diff_line_no);
+}
+
+#[test]
+fn test_parse_word_diff_porcelain() {
+    let buf = include_str!("fixtures/word_diff_porcelain.diff");
+    let mut patch = PatchSet::new();
+    patch
+        .parse_word_diff(buf, WordDiffFormat::Porcelain)
+        .unwrap();
+    check_word_diff(&patch);
+    assert_eq!(8, patch[0][0][1].diff_line_no);
+}
+
+#[test]
+fn test_word_diff_as_unified_diff() {
+    let buf = include_str!("fixtures/word_diff_plain.diff");
+    let mut patch = PatchSet::new();
+    patch.parse_word_diff(buf, WordDiffFormat::Plain).unwrap();
+    let unified: PatchSet = patch.to_string().parse().unwrap();
+    assert_eq!(patch.to_string(), unified.to_string());
+    assert!(unified[1][0].lines().iter().all(|l| l.segments.is_empty()));
+}
+
+#[test]
+fn test_word_diff_lines_like_headers() {
+    let buf = "diff --git a/doc.tex b/doc.tex
+index 1111111..2222222 100644
+--- a/doc.tex
++++ b/doc.tex
+@@ -1,3 +1,3 @@
+\\section{Intro}
+diff the files first
+[-then-]{+and then+} patch
+\\ No newline at end of file
+diff --git a/other b/other
+index 3333333..4444444 100644
+--- a/other
++++ b/other
+@@ -1 +1 @@
+[-a-]{+b+}
+";
+    let mut patch = PatchSet::new();
+    patch.parse_word_diff(buf, WordDiffFormat::Plain).unwrap();
+    assert_eq!(2, patch.len());
+
+    let hunk = &patch[0][0];
+    assert_eq!((3, 3), (hunk.source_length, hunk.target_length));
+    assert_eq!("\\section{Intro}", hunk[0].value);
+    assert!(hunk[0].is_context());
+    assert_eq!("diff the files first", hunk[1].value);
+    assert!(hunk[1].is_context());
+    assert_eq!((1, 1), (hunk.removed(), hunk.added()));
+    assert_eq!(LineType::Empty, hunk[4].line_type);
+    assert_eq!((1, 1), (patch[1].removed(), patch[1].added()));
+}